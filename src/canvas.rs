@@ -31,7 +31,7 @@ impl<'buf, Colour:Copy> Canvas<'buf, Colour> {
     /// 
     /// # Errors
     /// `SizeMismatchError` on invalid canvas size
-    pub fn new(buffer: &'buf mut [Colour], size: V2) -> Result<Self, SizeMismatchError> {
+    pub const fn new(buffer: &'buf mut [Colour], size: V2) -> Result<Self, SizeMismatchError> {
         if size.x as usize * size.y as usize == buffer.len() {
             Ok(Self { buffer, sizex: size.x })
         }
@@ -41,11 +41,24 @@ impl<'buf, Colour:Copy> Canvas<'buf, Colour> {
     }
 
     /// Get a pixel by coordinates
+    ///
+    /// Returns `None` outside of the canvas.
+    ///
+    /// # Example
+    /// ```
+    /// # use draw_i16::*;
+    /// let mut buffer = [0u8; 3*5];
+    /// let canvas = Canvas::<u8>::new(&mut buffer, v2(5,3)).unwrap();
+    /// assert_eq!(canvas.get_pixel(v2(4, 2)), Some(0));
+    /// assert_eq!(canvas.get_pixel(v2(5, 3)), None);
+    /// assert_eq!(canvas.get_pixel(v2(5, 0)), None);
+    /// assert_eq!(canvas.get_pixel(v2(0, 3)), None);
+    /// ```
     #[must_use]
     pub fn get_pixel(&self, point: V2) -> Option<Colour> {
         let sz = self._size();
-        if 0 <= point.x && point.x <= sz.x &&
-           0 <= point.y && point.y <= sz.y
+        if 0 <= point.x && point.x < sz.x &&
+           0 <= point.y && point.y < sz.y
            {
             Some(self.buffer[point.x as usize + point.y as usize*self.sizex as usize])
         }
@@ -54,12 +54,284 @@ impl Colour565 {
     pub const YELLOW : Self = Self::new(0xFF, 0xFF, 0x00);
 }
 
+impl Colour565 {
+    /// Parse a `"#RRGGBB"`, `"RRGGBB"` or `"#RGB"` hex string
+    ///
+    /// Returns `None` on invalid input. The function is `const`, see
+    /// [`Colour565::hex`] for the compile-time checked variant.
+    #[must_use]
+    pub const fn from_hex(s: &str) -> Option<Self> {
+        const fn digit(c: u8) -> Option<u8> {
+            match c {
+                b'0'..=b'9' => Some(c - b'0'),
+                b'a'..=b'f' => Some(c - b'a' + 10),
+                b'A'..=b'F' => Some(c - b'A' + 10),
+                _ => None,
+            }
+        }
+        const fn component(s: &[u8], idx: usize, long: bool) -> Option<u8> {
+            if long {
+                match (digit(s[2*idx]), digit(s[2*idx+1])) {
+                    (Some(hi), Some(lo)) => Some(hi*16 + lo),
+                    _ => None,
+                }
+            }
+            else {
+                match digit(s[idx]) {
+                    Some(d) => Some(d*17),
+                    None => None,
+                }
+            }
+        }
+
+        let s = s.as_bytes();
+        let s = match s {
+            [b'#', rest @ ..] => rest,
+            _ => s,
+        };
+        let long = match s.len() {
+            6 => true,
+            3 => false,
+            _ => return None,
+        };
+        match (component(s, 0, long), component(s, 1, long), component(s, 2, long)) {
+            (Some(r), Some(g), Some(b)) => Some(Self::new(r, g, b)),
+            _ => None,
+        }
+    }
+
+    /// Parse a hex colour string, panic on invalid input
+    ///
+    /// Intended for `const` colour tables, where the panic becomes a compile error.
+    ///
+    /// ```
+    /// # use draw_i16::*;
+    /// const ACCENT: Colour565 = Colour565::hex("#FF8000");
+    /// assert_eq!(ACCENT, Colour565::new(0xFF, 0x80, 0x00));
+    /// ```
+    #[must_use]
+    pub const fn hex(s: &str) -> Self {
+        match Self::from_hex(s) {
+            Some(c) => c,
+            None => panic!("invalid hex colour"),
+        }
+    }
+
+    /// Create a colour from hue (degrees, taken modulo 360), saturation and value
+    #[must_use]
+    pub const fn from_hsv(h: u16, s: u8, v: u8) -> Self {
+        let (h, s, v) = (h as i32 % 360, s as i32, v as i32);
+        if s == 0 {
+            return Self::new(v as u8, v as u8, v as u8);
+        }
+        let rem = (h % 60) * 255 / 60;
+        let p = v * (255 - s) / 255;
+        let q = v * (255 - s * rem / 255) / 255;
+        let t = v * (255 - s * (255 - rem) / 255) / 255;
+        let (r, g, b) = match h / 60 {
+            0 => (v, t, p),
+            1 => (q, v, p),
+            2 => (p, v, t),
+            3 => (p, q, v),
+            4 => (t, p, v),
+            _ => (v, p, q),
+        };
+        Self::new(r as u8, g as u8, b as u8)
+    }
+
+    /// Represent a colour as hue (0..360 degrees), saturation and value
+    #[must_use]
+    pub const fn hsv(self) -> (u16, u8, u8) {
+        let (max, delta) = self.max_and_delta();
+        let s = if max == 0 {0} else {delta * 255 / max};
+        (self.hue(max, delta), s as u8, max as u8)
+    }
+
+    /// Create a colour from hue (degrees, taken modulo 360), saturation and lightness
+    #[must_use]
+    pub const fn from_hsl(h: u16, s: u8, l: u8) -> Self {
+        let (h, s, l) = (h as i32 % 360, s as i32, l as i32);
+        let chroma = (255 - (2*l - 255).abs()) * s / 255;
+        let x = chroma * (60 - (h % 120 - 60).abs()) / 60;
+        let m = l - chroma/2;
+        let (r, g, b) = match h / 60 {
+            0 => (chroma, x, 0),
+            1 => (x, chroma, 0),
+            2 => (0, chroma, x),
+            3 => (0, x, chroma),
+            4 => (x, 0, chroma),
+            _ => (chroma, 0, x),
+        };
+        Self::new((r+m) as u8, (g+m) as u8, (b+m) as u8)
+    }
+
+    /// Represent a colour as hue (0..360 degrees), saturation and lightness
+    #[must_use]
+    pub const fn hsl(self) -> (u16, u8, u8) {
+        let (max, delta) = self.max_and_delta();
+        let min = max - delta;
+        let s = if delta == 0 {0} else {delta * 255 / (255 - (max + min - 255).abs())};
+        (self.hue(max, delta), s as u8, ((max + min) / 2) as u8)
+    }
+
+    const fn max_and_delta(self) -> (i32, i32) {
+        let (r, g, b) = (self.r() as i32, self.g() as i32, self.b() as i32);
+        let max = if r > g {if r > b {r} else {b}} else if g > b {g} else {b};
+        let min = if r < g {if r < b {r} else {b}} else if g < b {g} else {b};
+        (max, max - min)
+    }
+
+    const fn hue(self, max: i32, delta: i32) -> u16 {
+        let (r, g, b) = (self.r() as i32, self.g() as i32, self.b() as i32);
+        let h = if delta == 0 {0}
+            else if max == r {60 * (g - b) / delta}
+            else if max == g {120 + 60 * (b - r) / delta}
+            else {240 + 60 * (r - g) / delta};
+        h.rem_euclid(360) as u16
+    }
+
+    /// Interpolate between two colours, `t = 0` gives `a` and `t = 255` gives `b`
+    #[must_use]
+    pub const fn lerp(a: Self, b: Self, t: u8) -> Self {
+        const fn channel(a: u8, b: u8, t: u8) -> u8 {
+            (a as i32 + (b as i32 - a as i32) * t as i32 / 255) as u8
+        }
+        Self::new(channel(a.r(), b.r(), t), channel(a.g(), b.g(), t), channel(a.b(), b.b(), t))
+    }
+
+    /// Move the colour towards white by `amount` of 255
+    #[must_use]
+    pub const fn lighten(self, amount: u8) -> Self {Self::lerp(self, Self::WHITE, amount)}
+
+    /// Move the colour towards black by `amount` of 255
+    #[must_use]
+    pub const fn darken(self, amount: u8) -> Self {Self::lerp(self, Self::BLACK, amount)}
+}
+
 impl core::fmt::Debug for Colour565 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "#{:x}{:x}{:x}", self.r(), self.g(), self.b())
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+/// Formats the colour as `#rrggbb`, which is parsed back by [`FromStr`](core::str::FromStr)
+impl core::fmt::Display for Colour565 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r(), self.g(), self.b())
+    }
+}
+
+/// An error returned when parsing an invalid hex colour string
+#[derive(Debug)]
+pub struct ParseColourError;
+
+impl core::str::FromStr for Colour565 {
+    type Err = ParseColourError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s).ok_or(ParseColourError)
     }
 }
 
+const fn swap_red_blue(raw: u16) -> u16 {
+    (raw & 0x001F) << 11 | (raw & 0x07E0) | raw >> 11
+}
+
+macro_rules! colour565_layout {
+    ($(#[$meta:meta])* $name:ident, $encode:path, $decode:path) => {
+        $(#[$meta])*
+        #[derive(Clone,Copy,PartialEq,Eq)]
+        #[repr(transparent)]
+        pub struct $name(u16);
+
+        impl $name {
+            #![allow(missing_docs)]
+
+            /// Create a colour from rgb
+            #[must_use]
+            pub const fn new(r: u8, g: u8, b: u8) -> Self {
+                Self::from_colour565(Colour565::new(r, g, b))
+            }
+
+            /// Convert from the native-endian RGB565 [`Colour565`]
+            #[must_use]
+            pub const fn from_colour565(c: Colour565) -> Self {Self($encode(c.0))}
+
+            /// Convert to the native-endian RGB565 [`Colour565`]
+            #[must_use]
+            pub const fn to_colour565(self) -> Colour565 {Colour565($decode(self.0))}
+
+            /// Get the raw value exactly as stored in memory
+            #[must_use]
+            pub const fn raw(self) -> u16 {self.0}
+
+            /// Represent a colour as Red, Green, Blue components
+            #[must_use]
+            pub const fn rgb(self) -> (u8,u8,u8) {self.to_colour565().rgb()}
+
+            /// View a pixel buffer as bytes in the display transfer order
+            #[must_use]
+            pub const fn slice_as_bytes(pixels: &[Self]) -> &[u8] {
+                // Safety: the type is a transparent u16 without padding
+                unsafe { core::slice::from_raw_parts(pixels.as_ptr().cast::<u8>(), pixels.len() * 2) }
+            }
+
+            pub const CYAN : Self = Self::from_colour565(Colour565::CYAN);
+            pub const BLACK : Self = Self::from_colour565(Colour565::BLACK);
+            pub const BLUE : Self = Self::from_colour565(Colour565::BLUE);
+            pub const MAGENTA : Self = Self::from_colour565(Colour565::MAGENTA);
+            pub const GRAY : Self = Self::from_colour565(Colour565::GRAY);
+            pub const GREEN : Self = Self::from_colour565(Colour565::GREEN);
+            pub const LIME : Self = Self::from_colour565(Colour565::LIME);
+            pub const MAROON : Self = Self::from_colour565(Colour565::MAROON);
+            pub const NAVY : Self = Self::from_colour565(Colour565::NAVY);
+            pub const OLIVE : Self = Self::from_colour565(Colour565::OLIVE);
+            pub const PURPLE : Self = Self::from_colour565(Colour565::PURPLE);
+            pub const RED : Self = Self::from_colour565(Colour565::RED);
+            pub const SILVER : Self = Self::from_colour565(Colour565::SILVER);
+            pub const TEAL : Self = Self::from_colour565(Colour565::TEAL);
+            pub const WHITE : Self = Self::from_colour565(Colour565::WHITE);
+            pub const YELLOW : Self = Self::from_colour565(Colour565::YELLOW);
+        }
+
+        impl From<Colour565> for $name {
+            fn from(c: Colour565) -> Self {Self::from_colour565(c)}
+        }
+
+        impl From<$name> for Colour565 {
+            fn from(c: $name) -> Self {c.to_colour565()}
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.to_colour565().fmt(f)
+            }
+        }
+    };
+}
+
+colour565_layout!(
+    /// A RGB565 pixel stored big-endian, as expected by most SPI displays
+    /// (ST7789, ILI9341 etc.) in 8-bit transfer mode
+    ///
+    /// A `Canvas<Colour565Be>` buffer may be sent to the panel as is,
+    /// see [`Colour565Be::slice_as_bytes`].
+    Colour565Be, u16::to_be, u16::from_be
+);
+
+colour565_layout!(
+    /// A BGR565 pixel (blue in the high bits), native-endian
+    Bgr565, swap_red_blue, swap_red_blue
+);
+
+colour565_layout!(
+    /// A BGR565 pixel (blue in the high bits) stored big-endian
+    Bgr565Be, bgr565_to_be, bgr565_from_be
+);
+
+const fn bgr565_to_be(raw: u16) -> u16 {swap_red_blue(raw).to_be()}
+const fn bgr565_from_be(raw: u16) -> u16 {swap_red_blue(u16::from_be(raw))}
+
 #[test]
 fn test_recreate() {
     let iter = (0..0xFF).step_by(5)
@@ -71,3 +343,50 @@ fn test_recreate() {
         assert_eq!(colour, Colour565::new(colour.r(), colour.g(), colour.b()));
     }
 }
+
+#[test]
+fn test_layouts() {
+    let colour = Colour565::new(0xF8, 0x14, 0x08);
+
+    let be = Colour565Be::from(colour);
+    assert_eq!(Colour565Be::slice_as_bytes(&[be]), &colour.rgb565().to_be_bytes());
+    assert_eq!(Colour565::from(be), colour);
+
+    let bgr = Bgr565::from(colour);
+    assert_eq!(bgr.raw(), Colour565::new(0x08, 0x14, 0xF8).rgb565());
+    assert_eq!(bgr.rgb(), colour.rgb());
+
+    let bgr_be = Bgr565Be::from(colour);
+    assert_eq!(Bgr565Be::slice_as_bytes(&[bgr_be]), &bgr.raw().to_be_bytes());
+    assert_eq!(Colour565::from(bgr_be), colour);
+}
+
+#[test]
+fn test_conversions() {
+    use std::string::ToString;
+
+    for colour in [Colour565::BLACK, Colour565::new(0x08, 0x04, 0x00), Colour565::TEAL, Colour565::WHITE] {
+        assert_eq!(colour.to_string().parse::<Colour565>().unwrap(), colour);
+    }
+    assert_eq!(format!("{:?}", Colour565::new(0x08, 0x04, 0x00)), "#080400");
+    assert_eq!(Colour565::from_hex("#f80"), Some(Colour565::new(0xFF, 0x88, 0x00)));
+    assert_eq!(Colour565::from_hex("#f800"), None);
+    assert_eq!(Colour565::from_hex("#gggggg"), None);
+
+    assert_eq!(Colour565::from_hsv(0, 255, 255), Colour565::RED);
+    assert_eq!(Colour565::from_hsv(120, 255, 255), Colour565::LIME);
+    assert_eq!(Colour565::from_hsl(240, 255, 128), Colour565::BLUE);
+    assert_eq!(Colour565::BLUE.hsv(), (240, 255, 248));
+    for colour in [Colour565::RED, Colour565::NAVY, Colour565::OLIVE, Colour565::new(0x40, 0xC0, 0x80)] {
+        let (h, s, v) = colour.hsv();
+        assert_eq!(Colour565::from_hsv(h, s, v), colour);
+        let (h, s, l) = colour.hsl();
+        let back = Colour565::from_hsl(h, s, l);
+        assert!(back.r().abs_diff(colour.r()) <= 8 && back.g().abs_diff(colour.g()) <= 8 && back.b().abs_diff(colour.b()) <= 8);
+    }
+
+    assert_eq!(Colour565::lerp(Colour565::BLACK, Colour565::WHITE, 0), Colour565::BLACK);
+    assert_eq!(Colour565::lerp(Colour565::BLACK, Colour565::WHITE, 255), Colour565::WHITE);
+    assert_eq!(Colour565::RED.darken(255), Colour565::BLACK);
+    assert_eq!(Colour565::RED.lighten(255), Colour565::WHITE);
+}
@@ -0,0 +1,111 @@
+//! Compositor: merge a stack of canvases onto a drawable
+
+use super::{Canvas, Drawable, V2, v2};
+
+/// One layer of a [`Compositor`]
+pub struct Layer<'c, 'buf, Colour:Copy> {
+    /// The layer pixels
+    pub canvas: &'c Canvas<'buf, Colour>,
+    /// Whether the layer is composed
+    pub visible: bool,
+    /// Location of the layer top left corner on the target
+    pub offset: V2,
+    /// The transparent colour: the pixels of this colour show the layers below
+    pub key: Option<Colour>,
+}
+
+impl<'c, 'buf, Colour:Copy> Layer<'c, 'buf, Colour> {
+    /// Create a visible opaque layer at the target origin
+    pub const fn new(canvas: &'c Canvas<'buf, Colour>) -> Self {
+        Self{canvas, visible: true, offset: v2(0, 0), key: None}
+    }
+
+    /// Set the transparent colour
+    #[must_use]
+    pub const fn with_key(self, key: Colour) -> Self {
+        Self{key: Some(key), ..self}
+    }
+
+    /// Set the location on the target
+    #[must_use]
+    pub const fn with_offset(self, offset: V2) -> Self {
+        Self{offset, ..self}
+    }
+}
+
+/// A stack of `N` canvas layers composed onto a target drawable
+///
+/// The layers are ordered bottom to top: a pixel gets the colour of the topmost
+/// visible layer covering it with a non-transparent colour. The pixels not covered
+/// by any layer are left untouched on the target. The runs of equal colour
+/// are written with the target's `_hline`.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*;
+/// let mut bg_buf = [b'.'; 4];
+/// let mut popup_buf = [b' '; 2];
+/// let mut bg = Canvas::new(&mut bg_buf, v2(4,1)).unwrap();
+/// let mut popup = Canvas::new(&mut popup_buf, v2(2,1)).unwrap();
+/// bg.pixel(v2(3, 0), b'b');
+/// popup.pixel(v2(1, 0), b'p');
+///
+/// let mut layers = Compositor {layers: [
+///     Layer::new(&bg),
+///     Layer::new(&popup).with_key(b' ').with_offset(v2(1, 0)),
+/// ]};
+///
+/// let mut screen_buf = [b'?'; 4];
+/// let mut screen = Canvas::new(&mut screen_buf, v2(4,1)).unwrap();
+/// layers.compose(&mut screen);
+/// assert_eq!(&screen_buf, b"..pb");
+///
+/// layers.layers[1].visible = false;
+/// let mut screen = Canvas::new(&mut screen_buf, v2(4,1)).unwrap();
+/// layers.compose_rect(&mut screen, v2(2, 0), v2(2, 0));
+/// assert_eq!(&screen_buf, b"...b");
+/// ```
+pub struct Compositor<'c, 'buf, Colour:Copy, const N: usize> {
+    /// The layers, bottom to top
+    pub layers: [Layer<'c, 'buf, Colour>; N],
+}
+
+impl<Colour:Copy+PartialEq, const N: usize> Compositor<'_, '_, Colour, N> {
+    /// Get the composed colour at a target point
+    #[must_use]
+    pub fn colour_at(&self, pos: V2) -> Option<Colour> {
+        self.layers.iter().rev()
+            .filter(|layer| layer.visible)
+            .filter_map(|layer| layer.canvas.get_pixel(pos - layer.offset).filter(|&c| Some(c) != layer.key))
+            .next()
+    }
+
+    /// Compose the layers onto the whole `target`
+    pub fn compose(&self, target: &mut dyn Drawable<Colour>) {
+        let size = target.size();
+        self.compose_rect(target, v2(0, 0), size - v2(1, 1));
+    }
+
+    /// Compose the layers onto the `target` within the dirty rectangle `[p1, p2]`
+    pub fn compose_rect(&self, target: &mut dyn Drawable<Colour>, p1: V2, p2: V2) {
+        let size = target.size();
+        let (x1, x2) = (p1.x.min(p2.x).max(0), p1.x.max(p2.x).min(size.x - 1));
+        let (y1, y2) = (p1.y.min(p2.y).max(0), p1.y.max(p2.y).min(size.y - 1));
+
+        for y in y1..=y2 {
+            let mut run: Option<(i16, Colour)> = None;
+            for x in x1..=x2+1 {
+                let colour = if x <= x2 {self.colour_at(v2(x, y))} else {None};
+                match run {
+                    Some((_, c)) if Some(c) == colour => {}
+                    _ => {
+                        if let Some((start, c)) = run {
+                            target.horz_line(v2(start, y), (x - start) as u16, c);
+                        }
+                        run = colour.map(|c| (x, c));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Double buffer: render a frame, push only what has changed
+
+use super::{Canvas, V2, v2};
+use crate::canvas::SizeMismatchError;
+
+/// A changed part of a row: the columns `[x1, x2]` of the row `y`
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct RowDiff {
+    /// Row index
+    pub y: i16,
+    /// First changed column
+    pub x1: i16,
+    /// Last changed column
+    pub x2: i16,
+}
+
+/// A pair of same-sized pixel buffers: the front one is what is shown
+/// on the display, the back one is where the new frame is drawn
+///
+/// Draw the frame on [`DoubleBuffer::back`], send the differences
+/// ([`DoubleBuffer::diff_rows`] or [`DoubleBuffer::diff_rects`]) from the back
+/// buffer to the display and then [`DoubleBuffer::swap`] the buffers.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*;
+/// let mut front = [b'.'; 4*3];
+/// let mut back = [b'.'; 4*3];
+/// let mut frames = DoubleBuffer::new(&mut front, &mut back, v2(4,3)).unwrap();
+///
+/// frames.back().pixel(v2(1, 0), b'o');
+/// frames.back().pixel(v2(2, 1), b'o');
+/// assert_eq!(frames.diff_rects().collect::<Vec<_>>(), [(v2(1, 0), v2(2, 1))]);
+/// frames.swap();
+///
+/// frames.back().clear(b'.');
+/// frames.back().pixel(v2(1, 0), b'o');
+/// assert_eq!(frames.diff_rows().collect::<Vec<_>>(), [RowDiff{y: 1, x1: 2, x2: 2}]);
+/// ```
+pub struct DoubleBuffer<'buf, Colour:Copy> {
+    front: &'buf mut [Colour],
+    back: &'buf mut [Colour],
+    size: V2,
+}
+
+impl<'buf, Colour:Copy+PartialEq> DoubleBuffer<'buf, Colour> {
+    /// Create a new double buffer on the existing buffers
+    ///
+    /// Both buffer sizes must be exactly size.x * size.y
+    ///
+    /// # Errors
+    /// `SizeMismatchError` on invalid buffer sizes
+    pub const fn new(front: &'buf mut [Colour], back: &'buf mut [Colour], size: V2) -> Result<Self, SizeMismatchError> {
+        let len = size.x as usize * size.y as usize;
+        if front.len() == len && back.len() == len {
+            Ok(Self{front, back, size})
+        }
+        else {
+            Err(SizeMismatchError)
+        }
+    }
+
+    /// Get the buffer size
+    #[must_use]
+    pub const fn size(&self) -> V2 {self.size}
+
+    /// Get a canvas to draw the next frame on
+    pub fn back(&mut self) -> Canvas<'_, Colour> {
+        Canvas::new(self.back, self.size).expect("the buffer size is checked by new()")
+    }
+
+    /// Get the pixels of the next frame
+    #[must_use]
+    pub const fn back_pixels(&self) -> &[Colour] {self.back}
+
+    /// Get the pixels shown on the display
+    #[must_use]
+    pub const fn front_pixels(&self) -> &[Colour] {self.front}
+
+    /// Make the back buffer the front one
+    ///
+    /// The new back buffer contains the frame before the previous one,
+    /// so either draw the next frame completely or call [`DoubleBuffer::sync_back`].
+    pub const fn swap(&mut self) {
+        core::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Copy the front buffer to the back one to draw the next frame incrementally
+    pub const fn sync_back(&mut self) {
+        self.back.copy_from_slice(self.front);
+    }
+
+    fn row_diff(&self, y: i16) -> Option<RowDiff> {
+        let width = self.size.x as usize;
+        let start = y as usize * width;
+        let front = &self.front[start..start + width];
+        let back = &self.back[start..start + width];
+        let x1 = front.iter().zip(back).position(|(f, b)| f != b)?;
+        let x2 = front.iter().zip(back).rposition(|(f, b)| f != b)?;
+        Some(RowDiff{y, x1: x1 as i16, x2: x2 as i16})
+    }
+
+    /// Iterate over the changed part of every changed row
+    pub fn diff_rows(&self) -> impl Iterator<Item=RowDiff> + '_ {
+        (0..self.size.y).filter_map(move |y| self.row_diff(y))
+    }
+
+    /// Iterate over the rectangles `[p1, p2]` bounding the runs of changed rows
+    pub fn diff_rects(&self) -> impl Iterator<Item=(V2, V2)> + '_ {
+        let mut rows = self.diff_rows().peekable();
+        core::iter::from_fn(move || {
+            let first = rows.next()?;
+            let (mut p1, mut p2) = (v2(first.x1, first.y), v2(first.x2, first.y));
+            while let Some(next) = rows.next_if(|next| next.y == p2.y + 1) {
+                p1.x = p1.x.min(next.x1);
+                p2 = v2(p2.x.max(next.x2), next.y);
+            }
+            Some((p1, p2))
+        })
+    }
+}
@@ -2,7 +2,7 @@
 
 use ranged_integers::r;
 
-use crate::font::GlyphProvider;
+use crate::font::{GlyphProvider, TextStyle};
 use crate::{V2, v2};
 
 #[cfg(any(feature="font_data", doc))]
@@ -162,6 +162,146 @@ pub trait DrawableMethods<Colour:Copy>: Drawable<Colour>+Sized {
     fn text(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour) {
         <dyn Drawable<Colour>>::text(self, s, fontsize, pos, colour)
     }
+    /// Paint a synthetically styled glyph using the user-defined char code to glyph converter
+    /// 
+    /// * `tables` - the code-to-glyph steps converter (see [`GlyphProvider`])
+    /// * `ch` - char to display
+    /// * `fontsize` - size of one char (consider using [`fontsize_to_glyphsize`](crate::font::fontsize_to_glyphsize) output)
+    /// * `pos` - location of top left corner of the character
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `style` - weight, slant and decorations (see [`TextStyle`])
+    #[inline] fn symbol_styled_with_provider<G:GlyphProvider>(&mut self, tables: G, ch: char, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle) {
+        <dyn Drawable<Colour>>::symbol_styled_with_provider(self, tables, ch, fontsize, pos, colour, style);
+    }
+
+    /// Paint a synthetically styled line of text using the user-defined char code to glyph converter
+    /// 
+    /// * `tables` - the code-to-glyph steps converter (see [`GlyphProvider`])
+    /// * `s` - string to display
+    /// * `fontsize` - size of one char (consider using [`fontsize_to_glyphsize`](crate::font::fontsize_to_glyphsize) output)
+    /// * `pos` - location of top left corner of the character
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `style` - weight, slant and decorations (see [`TextStyle`])
+    #[inline] fn text_styled_with_provider<G:GlyphProvider>(&mut self, tables: G, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle) {
+        <dyn Drawable<Colour>>::text_styled_with_provider(self, tables, s, fontsize, pos, colour, style);
+    }
+
+    /// Paint a synthetically styled line of text using the builtin font
+    /// 
+    /// * `s` - string to display
+    /// * `pos` - location of top left corner of the character
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `style` - weight, slant and decorations (see [`TextStyle`])
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    #[inline]
+    fn text_styled(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle) {
+        <dyn Drawable<Colour>>::text_styled(self, s, fontsize, pos, colour, style)
+    }
+
+    /// Paint a line of text with its cells filled with a background colour using the user-defined char code to glyph converter
+    /// 
+    /// Every pixel of the text cells is written once, so a changing text may be
+    /// updated in place without flicker. The cells are [`advance`](crate::font::GlyphProvider::advance)
+    /// pixels wide; the fonts larger than 64x64 pixels are drawn over a filled rectangle instead.
+    /// 
+    /// * `tables` - the code-to-glyph steps converter (see [`GlyphProvider`])
+    /// * `s` - string to display
+    /// * `fontsize` - size of one char (consider using [`fontsize_to_glyphsize`](crate::font::fontsize_to_glyphsize) output)
+    /// * `pos` - location of top left corner of the character
+    /// * `fg` - parameter to draw the symbol pixels with
+    /// * `bg` - parameter to draw the rest of the cell pixels with
+    #[inline] fn text_with_background_with_provider<G:GlyphProvider>(&mut self, tables: G, s: &str, fontsize: V2, pos: V2, fg: Colour, bg: Colour) {
+        <dyn Drawable<Colour>>::text_with_background_with_provider(self, tables, s, fontsize, pos, fg, bg);
+    }
+
+    /// Paint a line of text with its cells filled with a background colour using the builtin font
+    /// 
+    /// * `s` - string to display
+    /// * `pos` - location of top left corner of the character
+    /// * `fg` - parameter to draw the symbol pixels with
+    /// * `bg` - parameter to draw the rest of the cell pixels with
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    #[inline]
+    fn text_with_background(&mut self, s: &str, fontsize: V2, pos: V2, fg: Colour, bg: Colour) {
+        <dyn Drawable<Colour>>::text_with_background(self, s, fontsize, pos, fg, bg)
+    }
+
+    /// Paint a line of text over an outline or a shadow using the user-defined char code to glyph converter
+    /// 
+    /// * `tables` - the code-to-glyph steps converter (see [`GlyphProvider`])
+    /// * `s` - string to display
+    /// * `fontsize` - size of one char (consider using [`fontsize_to_glyphsize`](crate::font::fontsize_to_glyphsize) output)
+    /// * `pos` - location of top left corner of the character
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `style` - weight, slant and decorations (see [`TextStyle`])
+    /// * `effect` - the outline or shadow painted under the text (see [`TextEffect`](crate::font::TextEffect))
+    #[allow(clippy::too_many_arguments)]
+    #[inline] fn text_effect_with_provider<G:GlyphProvider>(&mut self, tables: G, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle, effect: crate::font::TextEffect<Colour>) {
+        <dyn Drawable<Colour>>::text_effect_with_provider(self, tables, s, fontsize, pos, colour, style, effect);
+    }
+
+    /// Paint a line of text over an outline or a shadow using the builtin font
+    /// 
+    /// * `s` - string to display
+    /// * `pos` - location of top left corner of the character
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `style` - weight, slant and decorations (see [`TextStyle`])
+    /// * `effect` - the outline or shadow painted under the text (see [`TextEffect`](crate::font::TextEffect))
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    #[inline]
+    fn text_effect(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle, effect: crate::font::TextEffect<Colour>) {
+        <dyn Drawable<Colour>>::text_effect(self, s, fontsize, pos, colour, style, effect)
+    }
+
+    /// Paint a text in a direction using the user-defined char code to glyph converter
+    /// 
+    /// * `tables` - the code-to-glyph steps converter (see [`GlyphProvider`])
+    /// * `s` - string to display, the newlines start the next lines
+    /// * `fontsize` - size of one char (consider using [`fontsize_to_glyphsize`](crate::font::fontsize_to_glyphsize) output)
+    /// * `pos` - location of top left corner of the text bounds (see [`measure_text_directed`](crate::text::measure_text_directed))
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `direction` - rotation of the text (see [`TextDirection`](crate::text::TextDirection))
+    #[inline] fn text_directed_with_provider<G:GlyphProvider>(&mut self, tables: G, s: &str, fontsize: V2, pos: V2, colour: Colour, direction: crate::text::TextDirection) {
+        <dyn Drawable<Colour>>::text_directed_with_provider(self, tables, s, fontsize, pos, colour, direction);
+    }
+
+    /// Paint a text in a direction using the builtin font
+    /// 
+    /// * `s` - string to display, the newlines start the next lines
+    /// * `pos` - location of top left corner of the text bounds (see [`measure_text_directed`](crate::text::measure_text_directed))
+    /// * `colour` - parameter to draw the symbol pixels with
+    /// * `direction` - rotation of the text (see [`TextDirection`](crate::text::TextDirection))
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    #[inline]
+    fn text_directed(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour, direction: crate::text::TextDirection) {
+        <dyn Drawable<Colour>>::text_directed(self, s, fontsize, pos, colour, direction)
+    }
+
+    /// Paint a text laid out in a [`TextBox`](crate::text::TextBox) using the user-defined char code to glyph converter
+    /// 
+    /// * `tables` - the code-to-glyph steps converter (see [`GlyphProvider`])
+    /// * `textbox` - the rectangle, font size and alignment of the text
+    /// * `s` - string to display, may contain newlines
+    /// * `colour` - parameter to draw the symbol pixels with
+    #[inline] fn text_box_with_provider<G:GlyphProvider>(&mut self, tables: G, textbox: &crate::text::TextBox, s: &str, colour: Colour) {
+        <dyn Drawable<Colour>>::text_box_with_provider(self, tables, textbox, s, colour);
+    }
+
+    /// Paint a text laid out in a [`TextBox`](crate::text::TextBox) using the builtin font
+    /// 
+    /// * `textbox` - the rectangle, font size and alignment of the text
+    /// * `s` - string to display, may contain newlines
+    /// * `colour` - parameter to draw the symbol pixels with
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    #[inline]
+    fn text_box(&mut self, textbox: &crate::text::TextBox, s: &str, colour: Colour) {
+        <dyn Drawable<Colour>>::text_box(self, textbox, s, colour)
+    }
 }
 
 impl<T, Colour:Copy> DrawableMethods<Colour> for T where T: Drawable<Colour> {}
@@ -598,45 +738,133 @@ impl<Colour:Copy> dyn Drawable<Colour>+'_ {
     #[doc(cfg(feature="font_data"))]
     /// Generate and render a char sequence
     pub fn text(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour) {
-        for (i,c) in s.chars().enumerate() {
-            let posx = pos.x + fontsize.x*i as i16;
-            if posx < self.size().x && posx + fontsize.x > 0 {
-                self.symbol(c, fontsize, V2::new(posx, pos.y), colour);
-            }
-        }
+        self.text_with_provider(&font_data::TABLES as &[GlyphTable], s, fontsize, pos, colour);
     }
 
     /// Generate and render a text with the user-defined steps
+    ///
+    /// The chars are placed by the [`advance`](crate::font::GlyphProvider::advance)
+    /// and [`kerning`](crate::font::GlyphProvider::kerning) of the `tables`:
+    /// wrap them into [`Proportional`](crate::font::Proportional) for the proportional text
+    /// and [`Kerned`](crate::font::Kerned) for the kerning pairs.
     pub fn text_with_provider(&mut self, tables: impl crate::font::GlyphProvider, s: &str, fontsize: V2, pos: V2, colour: Colour) {
-        for (i,c) in s.chars().enumerate() {
-            let posx = pos.x + fontsize.x*i as i16;
-            if posx < self.size().x && posx + fontsize.x > 0 {
-                self.symbol_with_provider(tables, c, fontsize, V2::new(posx, pos.y), colour);
+        self.text_styled_with_provider(tables, s, fontsize, pos, colour, &TextStyle::REGULAR);
+    }
+
+    /// Generate and render a synthetically styled text with the user-defined steps
+    pub fn text_styled_with_provider(&mut self, tables: impl crate::font::GlyphProvider, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle) {
+        let margin = style.margin(fontsize);
+        let mut posx = pos.x;
+        let mut prev = None;
+        for c in s.chars() {
+            if let Some(prev) = prev.replace(c) {
+                posx += tables.kerning(prev, c, fontsize);
+            }
+            if posx - margin.x < self.size().x && posx + fontsize.x + margin.x > 0 {
+                self.symbol_styled_with_provider(tables, c, fontsize, V2::new(posx, pos.y), colour, &TextStyle{underline: false, strikethrough: false, overline: false, ..*style});
+            }
+            posx += tables.advance(c, fontsize);
+        }
+        if posx > pos.x {
+            style.decorate(self, pos.x, posx - 1, pos.y, fontsize, colour);
+        }
+    }
+
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    /// Generate and render a synthetically styled char sequence
+    pub fn text_styled(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle) {
+        self.text_styled_with_provider(&font_data::TABLES as &[GlyphTable], s, fontsize, pos, colour, style);
+    }
+
+    /// Generate and render a text filling the cell backgrounds with the user-defined steps
+    pub fn text_with_background_with_provider(&mut self, tables: impl crate::font::GlyphProvider, s: &str, fontsize: V2, pos: V2, fg: Colour, bg: Colour) {
+        crate::text::draw_with_background(self, tables, s, fontsize, pos, fg, bg);
+    }
+
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    /// Generate and render a char sequence filling the cell backgrounds
+    pub fn text_with_background(&mut self, s: &str, fontsize: V2, pos: V2, fg: Colour, bg: Colour) {
+        self.text_with_background_with_provider(&font_data::TABLES as &[GlyphTable], s, fontsize, pos, fg, bg);
+    }
+
+    /// Generate and render a styled text over an outline or a shadow with the user-defined steps
+    #[allow(clippy::too_many_arguments)]
+    pub fn text_effect_with_provider(&mut self, tables: impl crate::font::GlyphProvider, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle, effect: crate::font::TextEffect<Colour>) {
+        use crate::font::TextEffect;
+        match effect {
+            TextEffect::Outline{colour: outline, width} => {
+                let widened = TextStyle{extra_width: style.extra_width.saturating_add(width.saturating_mul(2)), ..*style};
+                self.text_styled_with_provider(tables, s, fontsize, pos, outline, &widened);
+            }
+            TextEffect::Shadow{colour: shadow, offset} => {
+                self.text_styled_with_provider(tables, s, fontsize, pos + offset, shadow, style);
             }
         }
+        self.text_styled_with_provider(tables, s, fontsize, pos, colour, style);
+    }
+
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    /// Generate and render a styled char sequence over an outline or a shadow
+    pub fn text_effect(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle, effect: crate::font::TextEffect<Colour>) {
+        self.text_effect_with_provider(&font_data::TABLES as &[GlyphTable], s, fontsize, pos, colour, style, effect);
+    }
+
+    /// Generate and render a text in a direction with the user-defined steps
+    pub fn text_directed_with_provider(&mut self, tables: impl crate::font::GlyphProvider, s: &str, fontsize: V2, pos: V2, colour: Colour, direction: crate::text::TextDirection) {
+        crate::text::draw_directed(self, tables, s, fontsize, pos, colour, direction);
+    }
+
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    /// Generate and render a char sequence in a direction
+    pub fn text_directed(&mut self, s: &str, fontsize: V2, pos: V2, colour: Colour, direction: crate::text::TextDirection) {
+        self.text_directed_with_provider(&font_data::TABLES as &[GlyphTable], s, fontsize, pos, colour, direction);
+    }
+
+    /// Lay a text out in a box and render it with the user-defined steps
+    pub fn text_box_with_provider(&mut self, tables: impl crate::font::GlyphProvider, textbox: &crate::text::TextBox, s: &str, colour: Colour) {
+        textbox.draw_with_provider(self, tables, s, colour);
+    }
+
+    #[cfg(any(feature="font_data", doc))]
+    #[doc(cfg(feature="font_data"))]
+    /// Lay a text out in a box and render it
+    pub fn text_box(&mut self, textbox: &crate::text::TextBox, s: &str, colour: Colour) {
+        self.text_box_with_provider(&font_data::TABLES as &[GlyphTable], textbox, s, colour);
     }
 
     /// Generate and render a symbol with the user-defined steps
     pub fn symbol_with_provider(&mut self, tables: impl crate::font::GlyphProvider, ch: char, fontsize: V2, pos: V2, colour: Colour) {
-        let mut sten = crate::helpers::Stencil::new(self, pos, fontsize);
+        self.symbol_styled_with_provider(tables, ch, fontsize, pos, colour, &TextStyle::REGULAR);
+    }
+
+    /// Generate and render a synthetically styled symbol with the user-defined steps
+    ///
+    /// The decoration lines of the `style` are drawn over the whole cell.
+    pub fn symbol_styled_with_provider(&mut self, tables: impl crate::font::GlyphProvider, ch: char, fontsize: V2, pos: V2, colour: Colour, style: &TextStyle) {
+        let margin = style.margin(fontsize);
+        let mut sten = crate::helpers::Stencil::new(self, pos - margin, fontsize + margin + margin);
         if let Some(cmds) = tables.get_glyph(ch as u32) {
-            crate::font::draw_glyph(cmds, &mut sten, colour, tables);
+            crate::font::draw_glyph_in(cmds, &mut sten, colour, tables, fontsize, margin, style);
         }
         else {
             use crate::font::GlyphStep;
             use crate::font::GlyphCoord;
             use crate::font::GlyphConnectionType::Outline;
-            crate::font::draw_glyph([
+            crate::font::draw_glyph_in([
                 GlyphStep{coord: GlyphCoord{x: r!([] 0), y: r!([] 24)}, tp: Outline{thick: false, update: true}},
                 GlyphStep{coord: GlyphCoord{x: r!([] 12), y: r!([] 24)}, tp: Outline{thick: false, update: true}},
                 GlyphStep{coord: GlyphCoord{x: r!([] 12), y: r!([] 0)}, tp: Outline{thick: false, update: true}},
                 GlyphStep{coord: GlyphCoord{x: r!([] 0), y: r!([] 0)}, tp: Outline{thick: false, update: true}},
-            ].iter().cloned(), &mut sten, colour, tables);
+            ].iter().cloned(), &mut sten, colour, tables, fontsize, margin, style);
         }
+        style.decorate(self, pos.x, pos.x + fontsize.x - 1, pos.y, fontsize, colour);
     }
 }
 
-
 #[cfg(test)]
 pub(crate) fn canvas_to_string(cvs: &crate::canvas::Canvas<u8>) -> std::string::String {
     use std::fmt::Write;
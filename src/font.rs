@@ -11,12 +11,15 @@
 //! in [`crate::font_data::TABLES`](font_data/static.TABLES.html) constant
 //! There is a [gui tool](https://github.com/disiamylborane/draw-i16-fontviewer)
 //! to create or modify a glyph table.
+//!
+//! The text is monospaced by default, wrap a [`GlyphProvider`] into [`Proportional`]
+//! to place the chars by their glyph widths.
 
 use ranged_integers::{AsRanged, Ranged, r, rmatch};
 use crate::{v2, Drawable, V2};
 
 /// Part of glyph drawing step: the current action
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub enum GlyphConnectionType {
     /// Jump to a new location
     Break,
@@ -40,7 +43,7 @@ pub enum GlyphConnectionType {
 }
 
 /// Coordinates used in [`GlyphStep`]
-#[derive(Clone,Copy,PartialEq, Eq)]
+#[derive(Clone,Copy,PartialEq, Eq, Debug)]
 pub struct GlyphCoord {
     /// X coordinate
     pub x : Ranged<0,15>,
@@ -49,7 +52,7 @@ pub struct GlyphCoord {
 }
 
 /// One step of symbol drawing, contains of action and coordinates
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct GlyphStep {
     /// Current point to apply action to
     pub coord : GlyphCoord,
@@ -71,6 +74,115 @@ pub struct GlyphTable {
 pub trait GlyphProvider : Copy {
     /// Yield the set of steps for the specified char
     fn get_glyph(self, char: u32) -> Option<impl Iterator<Item=GlyphStep>>;
+
+    /// Get the horizontal distance from the char to the next one in pixels
+    ///
+    /// The default is monospaced: every char occupies the whole `fontsize.x`.
+    /// See [`Proportional`] for the glyph-dependent widths.
+    fn advance(self, _ch: char, fontsize: V2) -> i16 {
+        fontsize.x
+    }
+
+    /// Get the kerning adjustment stored for a pair of chars in the x glyph coordinate units
+    fn kerning_pair(self, _left: char, _right: char) -> i8 {
+        0
+    }
+
+    /// Get the pixels to add to the advance of the `left` char followed by the `right` one
+    ///
+    /// The default is monospaced: no kerning is applied. [`Proportional`] applies
+    /// the [`kerning_pair`](GlyphProvider::kerning_pair) adjustments, [`Kerned`]
+    /// applies its own pairs.
+    fn kerning(self, _left: char, _right: char, _fontsize: V2) -> i16 {
+        0
+    }
+}
+
+/// Glyph provider wrapper making the text proportional
+///
+/// The glyphs are the ones of the wrapped provider, but the advance of every char
+/// is derived from its [`glyph_advance`], so narrow glyphs like `i` take less room.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use draw_i16::font::*;
+/// # #[cfg(feature="font_data")] {
+/// let font = &font_data::TABLES[..];
+/// let fontsize = v2(9, 16);
+/// assert!(measure_text(Proportional(font), "il", fontsize).x < measure_text(font, "il", fontsize).x);
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Proportional<G:GlyphProvider>(pub G);
+
+impl<G:GlyphProvider> GlyphProvider for Proportional<G> {
+    fn get_glyph(self, char: u32) -> Option<impl Iterator<Item=GlyphStep>> {
+        self.0.get_glyph(char)
+    }
+
+    fn advance(self, ch: char, fontsize: V2) -> i16 {
+        self.0.get_glyph(ch as u32)
+            .map_or(fontsize.x, |steps| glyph_advance(steps, fontsize, self.0))
+    }
+
+    fn kerning_pair(self, left: char, right: char) -> i8 {
+        self.0.kerning_pair(left, right)
+    }
+
+    fn kerning(self, left: char, right: char, fontsize: V2) -> i16 {
+        match self.0.kerning_pair(left, right) {
+            0 => 0,
+            adjustment => kerning_to_pixels(adjustment, fontsize),
+        }
+    }
+}
+
+/// Glyph provider wrapper adding kerning pairs
+///
+/// The pairs `(left, right, adjustment)` must be sorted by `(left, right)`, the
+/// adjustment is in the x glyph coordinate units, negative moves the chars closer.
+/// A pair found in the table overrides the wrapped provider's kerning, the text
+/// without the pairs costs a binary search per char.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use draw_i16::font::*;
+/// # #[cfg(feature="font_data")] {
+/// static PAIRS: [(char, char, i8); 2] = [('A', 'V', -3), ('T', 'o', -2)];
+/// let font = Kerned(Proportional(&font_data::TABLES[..]), &PAIRS);
+/// let fontsize = v2(9, 16);
+/// assert!(measure_text(font, "AV", fontsize).x < measure_text(font.0, "AV", fontsize).x);
+/// assert_eq!(measure_text(font, "VA", fontsize), measure_text(font.0, "VA", fontsize));
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Kerned<G:GlyphProvider>(pub G, pub &'static [(char, char, i8)]);
+
+impl<G:GlyphProvider> Kerned<G> {
+    fn find_pair(self, left: char, right: char) -> Option<i8> {
+        self.1.binary_search_by(|&(l, r, _)| (l, r).cmp(&(left, right)))
+            .ok()
+            .map(|idx| self.1[idx].2)
+    }
+}
+
+impl<G:GlyphProvider> GlyphProvider for Kerned<G> {
+    fn get_glyph(self, char: u32) -> Option<impl Iterator<Item=GlyphStep>> {
+        self.0.get_glyph(char)
+    }
+
+    fn advance(self, ch: char, fontsize: V2) -> i16 {
+        self.0.advance(ch, fontsize)
+    }
+
+    fn kerning_pair(self, left: char, right: char) -> i8 {
+        self.find_pair(left, right).unwrap_or_else(|| self.0.kerning_pair(left, right))
+    }
+
+    fn kerning(self, left: char, right: char, fontsize: V2) -> i16 {
+        self.find_pair(left, right)
+            .map_or_else(|| self.0.kerning(left, right, fontsize), |adjustment| kerning_to_pixels(adjustment, fontsize))
+    }
 }
 
 /// Glyph provider that provides no glyphs
@@ -100,7 +212,7 @@ impl GlyphProvider for &[GlyphTable] {
         }
 
         for tbl in self {
-            let idx = ch as usize - tbl.basechar as usize;
+            let idx = (ch as usize).wrapping_sub(tbl.basechar as usize);
             if idx >= tbl.addr.len() {
                 continue;
             }
@@ -146,7 +258,7 @@ impl GlyphConnectionType {
 impl GlyphCoord {
     const fn gap_by_xsize(xsize: u8) -> u8 {
         let gap = xsize/5;
-        if (xsize-gap) % 2 == 0 {gap+1}
+        if (xsize-gap).is_multiple_of(2) {gap+1}
         else {gap}
     }
 
@@ -254,6 +366,117 @@ pub fn code_to_recall(code: Ranged<0, 0x3ffff>) -> (GlyphCoord, GlyphCoord) {
     (GlyphCoord{x: fst_x, y:fst_y},GlyphCoord{x: snd_x, y:snd_y})
 }
 
+/// Synthetic style of a text, derived from the glyph strokes
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use draw_i16::font::*;
+/// let style = TextStyle{underline: true, ..TextStyle::BOLD};
+/// let bounds = measure(|d| d.text_styled_with_provider(EmptyGlyphProvider, "ab", v2(9, 16), v2(0, 0), 1, &style));
+/// // The underline covers both char cells
+/// assert_eq!(bounds.map(|(p1, p2)| p2.x - p1.x + 1), Some(18));
+/// ```
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct TextStyle {
+    /// Stroke width multiplier: 1 for regular, 2 for bold, 0 is treated as 1
+    pub weight: u8,
+    /// Italic shear: the pixels a stroke is moved right per 16 pixels above the baseline,
+    /// 0 for upright
+    pub italic: i8,
+    /// Draw a line under the baseline
+    pub underline: bool,
+    /// Draw a line through the middle of the lowercase letters
+    pub strikethrough: bool,
+    /// Draw a line above the capitals
+    pub overline: bool,
+    /// Pixels added to every stroke width, used to draw the [`TextEffect::Outline`]
+    pub extra_width: u8,
+}
+
+/// A contrasting background of a text, see [`text_effect_with_provider`](crate::DrawableMethods::text_effect_with_provider)
+///
+/// The effect is painted with the same glyph strokes as the text itself,
+/// so it follows the glyph shapes at any size and style.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use draw_i16::font::*;
+/// let draw = |d: &mut BoundsProbe, effect| d.text_effect_with_provider(EmptyGlyphProvider, "a", v2(9, 16), v2(0, 0), 1, &TextStyle::REGULAR, effect);
+/// let plain = measure(|d| d.text_with_provider(EmptyGlyphProvider, "a", v2(9, 16), v2(0, 0), 1)).unwrap();
+/// let shadowed = measure(|d| draw(d, TextEffect::Shadow{colour: 0, offset: v2(2, 2)})).unwrap();
+/// assert_eq!(shadowed, (plain.0, plain.1 + v2(2, 2)));
+/// let outlined = measure(|d| draw(d, TextEffect::Outline{colour: 0, width: 1})).unwrap();
+/// assert_eq!(outlined.0.x, plain.0.x - 1);
+/// ```
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum TextEffect<Colour> {
+    /// The strokes widened by `width` pixels on every side
+    Outline {
+        /// Colour of the outline
+        colour: Colour,
+        /// Outline width in pixels
+        width: u8,
+    },
+    /// The text moved by `offset`
+    Shadow {
+        /// Colour of the shadow
+        colour: Colour,
+        /// Location of the shadow relative to the text
+        offset: V2,
+    },
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {Self::REGULAR}
+}
+
+impl TextStyle {
+    /// The glyphs as they are
+    pub const REGULAR: Self = Self{weight: 1, italic: 0, underline: false, strikethrough: false, overline: false, extra_width: 0};
+    /// Doubled stroke width
+    pub const BOLD: Self = Self{weight: 2, ..Self::REGULAR};
+    /// Slanted by 1/4
+    pub const ITALIC: Self = Self{italic: 4, ..Self::REGULAR};
+
+    /// Get the stroke width of the style
+    const fn stroke(self, thick: bool, glyphsize: V2) -> u8 {
+        let weight = if self.weight == 0 {1} else {self.weight};
+        line_width(thick, glyphsize).saturating_mul(weight).saturating_add(self.extra_width)
+    }
+
+    /// Get the baseline of a glyph cell
+    const fn baseline(glyphsize: V2) -> i16 {
+        24 * glyphsize.y / 31
+    }
+
+    /// Apply the italic shear to a glyph cell point
+    const fn shear(self, p: V2, glyphsize: V2) -> V2 {
+        v2(p.x + (Self::baseline(glyphsize) - p.y) * self.italic as i16 / 16, p.y)
+    }
+
+    /// Get the size of the cell border the styled glyph may paint over
+    #[must_use]
+    pub const fn margin(self, glyphsize: V2) -> V2 {
+        let extra = (self.stroke(true, glyphsize) - line_width(true, glyphsize)) as i16;
+        let slant = (self.italic as i16).abs() * glyphsize.y / 16;
+        v2(extra + slant + (self.italic != 0) as i16, extra)
+    }
+
+    /// Draw the enabled decoration lines from `x1` to `x2` over a text line
+    /// with the top at `y` in the `drawable` coordinates
+    pub(crate) fn decorate<Colour:Copy>(self, drawable: &mut dyn Drawable<Colour>, x1: i16, x2: i16, y: i16, glyphsize: V2, colour: Colour) {
+        let width = self.stroke(false, glyphsize);
+        let lines = [
+            (self.overline, 0),
+            (self.strikethrough, 17 * glyphsize.y / 31),
+            (self.underline, (Self::baseline(glyphsize) + glyphsize.y) / 2),
+        ];
+        for (_, dy) in lines.iter().filter(|line| line.0) {
+            drawable.line(v2(x1, y + dy), v2(x2, y + dy), colour, width);
+        }
+    }
+}
+
 /// Draw a glyph on Drawable.
 ///
 /// Glyph defined by `steps` is drawn on the `drawable`. The function uses the whole
@@ -262,10 +485,26 @@ pub fn code_to_recall(code: Ranged<0, 0x3ffff>) -> (GlyphCoord, GlyphCoord) {
 /// steps the glyph will be searched in the `recall_tables`, consider passing
 /// `&[GlyphTable]` or `EmptyGlyphProvider`.
 pub fn draw_glyph<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &mut dyn Drawable<Colour>, colour: Colour, recall_tables: impl GlyphProvider) {
+    draw_glyph_styled(steps, drawable, colour, recall_tables, &TextStyle::REGULAR);
+}
+
+/// Draw a glyph on Drawable with a synthetic style
+///
+/// Same as [`draw_glyph`], but the stroke widths are multiplied and the coordinates
+/// are sheared as the `style` says. The decoration lines are not drawn: they are
+/// a property of a text line rather than a glyph. The styled glyph may
+/// paint over the [`TextStyle::margin`] around the drawable.
+pub fn draw_glyph_styled<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &mut dyn Drawable<Colour>, colour: Colour, recall_tables: impl GlyphProvider, style: &TextStyle) {
+    let csize = drawable.size();
+    draw_glyph_in(steps, drawable, colour, recall_tables, csize, v2(0, 0), style);
+}
+
+/// Draw a `glyphsize` glyph with the top left corner at `origin`
+pub(crate) fn draw_glyph_in<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &mut dyn Drawable<Colour>, colour: Colour, recall_tables: impl GlyphProvider, csize: V2, origin: V2, style: &TextStyle) {
     let mut prevpoint: GlyphCoord = GlyphCoord{x: r!([] 0), y: r!([] 2)};
     let mut ctrlpoint: Option<GlyphCoord> = None;
 
-    let csize = drawable.size();
+    let real = |coord: GlyphCoord| origin + style.shear(coord.get_real_coords(csize), csize);
 
     for GlyphStep { coord, tp } in steps {
         match tp {
@@ -276,7 +515,7 @@ pub fn draw_glyph<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &
                 if let Some(cp) = ctrlpoint {
                     let char = recall_to_code(cp, coord);
                     if let Some(iter) = recall_tables.get_glyph(char.u32()) {
-                        draw_glyph(iter, drawable, colour, EmptyGlyphProvider);
+                        draw_glyph_in(iter, drawable, colour, EmptyGlyphProvider, csize, origin, style);
                     }
                 }
                 else {
@@ -285,19 +524,19 @@ pub fn draw_glyph<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &
                 ctrlpoint = None;
             }
             GlyphConnectionType::Outline { thick, update } => {
-                let width = line_width(thick, csize);
+                let width = style.stroke(thick, csize);
                 
                 if let Some(cp) = ctrlpoint {
-                    drawable.quad_spline(prevpoint.get_real_coords(csize), cp.get_real_coords(csize), coord.get_real_coords(csize), colour, width);
+                    drawable.quad_spline(real(prevpoint), real(cp), real(coord), colour, width);
                 }
                 else {
-                    drawable.line(prevpoint.get_real_coords(csize), coord.get_real_coords(csize), colour, width);
+                    drawable.line(real(prevpoint), real(coord), colour, width);
                 }
                 if update {prevpoint = coord;}
                 ctrlpoint = None;
             }
             GlyphConnectionType::Oval{right} => {
-                let width = line_width(true, csize);
+                let width = style.stroke(true, csize);
                 let prev = prevpoint.get_real_coords(csize);
                 let curr = coord.get_real_coords(csize);
 
@@ -307,6 +546,7 @@ pub fn draw_glyph<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &
                 else {
                     let center = if ((curr.x >= prev.x) == (curr.y >= prev.y)) == right {v2(curr.x, prev.y)} else {v2(prev.x, curr.y)};
                     let radii = curr-prev;
+                    let center = origin + style.shear(center, csize);
 
                     if right {
                         drawable.ellipse_at_center(center, (radii.x.abs(), radii.y.abs()), colour, [
@@ -333,7 +573,65 @@ pub fn draw_glyph<Colour:Copy>(steps: impl Iterator<Item=GlyphStep>, drawable: &
     }
 }
 
+/// Get the range of x glyph coordinates covered by a glyph
+///
+/// The recalled glyphs are searched in `recall_tables`.
+fn glyph_x_extent(steps: impl Iterator<Item=GlyphStep>, recall_tables: impl GlyphProvider) -> Option<(Ranged<0,15>, Ranged<0,15>)> {
+    let mut extent: Option<(Ranged<0,15>, Ranged<0,15>)> = None;
+    let mut add = |x: Ranged<0,15>| extent = Some(extent.map_or((x, x), |(lo, hi)| (lo.min(x), hi.max(x))));
+    let mut ctrlpoint: Option<GlyphCoord> = None;
+
+    for GlyphStep { coord, tp } in steps {
+        match tp {
+            GlyphConnectionType::Control => {
+                ctrlpoint = Some(coord);
+                continue;
+            }
+            GlyphConnectionType::Break => {
+                if let Some(cp) = ctrlpoint {
+                    let recalled = recall_tables.get_glyph(recall_to_code(cp, coord).u32())
+                        .and_then(|iter| glyph_x_extent(iter, EmptyGlyphProvider));
+                    if let Some((lo, hi)) = recalled {
+                        add(lo);
+                        add(hi);
+                    }
+                }
+                else {
+                    add(coord.x);
+                }
+            }
+            GlyphConnectionType::Outline{..} | GlyphConnectionType::Oval{..} => {
+                if let Some(cp) = ctrlpoint {
+                    add(cp.x);
+                }
+                add(coord.x);
+            }
+        }
+        ctrlpoint = None;
+    }
+    extent
+}
+
+/// Get the proportional advance of a glyph in pixels
+///
+/// The advance is the rightmost painted pixel of the glyph (derived from the maximum
+/// x of the glyph coordinates) plus the stroke width and the cell gap.
+/// An empty glyph (e.g. space) gets a half of `fontsize.x`.
+pub fn glyph_advance(steps: impl Iterator<Item=GlyphStep>, fontsize: V2, recall_tables: impl GlyphProvider) -> i16 {
+    glyph_x_extent(steps, recall_tables).map_or(fontsize.x / 2, |(_, hi)| {
+        let xgap = i16::from(GlyphCoord::gap_by_xsize(fontsize.x as u8));
+        let right = GlyphCoord{x: hi, y: r!([] 0)}.get_real_coords(fontsize).x;
+        (right + i16::from(line_width(true, fontsize)) + xgap / 2).clamp(1, fontsize.x)
+    })
+}
+
+/// Convert a kerning adjustment from the x glyph coordinate units to pixels
+const fn kerning_to_pixels(adjustment: i8, fontsize: V2) -> i16 {
+    let xglyph = fontsize.x - GlyphCoord::gap_by_xsize(fontsize.x as u8) as i16;
+    adjustment as i16 * xglyph / 12
+}
+
 const fn line_width(thick: bool, glyphsize: V2) -> u8 {
     let mainwidth = 1 + (glyphsize.x/16) as u8;
-    if thick {mainwidth} else {(mainwidth + 1) / 2}
+    if thick {mainwidth} else {mainwidth.div_ceil(2)}
 }
@@ -0,0 +1,251 @@
+//! A binary font container loadable at runtime
+//!
+//! The blob holds the same data as a set of [`GlyphTable`](crate::font::GlyphTable)s
+//! and may be stored anywhere (e.g. in an external flash) and read with
+//! [`FontBlob::new`] without copying. All the numbers are little-endian.
+//!
+//! | Offset             | Size              | Content                                                        |
+//! |--------------------|-------------------|----------------------------------------------------------------|
+//! | 0                  | 4                 | Magic `b"DI16"`                                                |
+//! | 4                  | 2                 | Format version, [`FORMAT_VERSION`]                             |
+//! | 6                  | 2                 | Table count `T`                                                |
+//! | 8                  | 4                 | Kerning pair count `K`                                         |
+//! | 12                 | 12 × `T`          | Table directory                                                |
+//! | 12 + 12 × `T`      | 9 × `K`           | Kerning pairs                                                  |
+//! | table offsets      |                   | Table bodies                                                   |
+//!
+//! A table directory entry is: the first char code (`u32`), the char count `N` (`u16`),
+//! the step data cell count `C` (`u16`) and the offset of the table body from the blob
+//! start (`u32`). The body is `N` glyph start addresses (`u16`, in steps, the
+//! [`GlyphTable::addr`](crate::font::GlyphTable::addr) array) followed by `C` cells of
+//! two packed steps, 3 bytes each (see [`GlyphStep::to_gdata`]).
+//!
+//! A kerning pair is the left char code (`u32`), the right char code (`u32`)
+//! and the adjustment (`i8`), the pairs are sorted by `(left, right)`.
+//!
+//! # Example
+//! ```
+//! # use draw_i16::font_blob::*; use draw_i16::font::GlyphProvider;
+//! let blob = [
+//!     b'D', b'I', b'1', b'6', 1, 0,  1, 0,  0, 0, 0, 0,  // header: 1 table, no kerning
+//!     b'a', 0, 0, 0,  1, 0,  1, 0,  24, 0, 0, 0,         // 'a', 1 glyph, 1 cell at 24
+//!     0, 0,                                              // 'a' starts at step 0
+//!     0x10, 0x50, 0xA2,                                  // 2 steps
+//! ];
+//! let font = FontBlob::new(&blob).unwrap();
+//! assert_eq!(font.get_glyph('a' as u32).map(Iterator::count), Some(2));
+//! assert!(font.get_glyph('b' as u32).is_none());
+//!
+//! assert_eq!(FontBlob::new(&blob[..20]).err(), Some(FontBlobError::Truncated));
+//! ```
+
+use crate::font::{GlyphProvider, GlyphStep};
+
+/// The magic bytes a font blob starts with
+pub const MAGIC: [u8; 4] = *b"DI16";
+
+/// The format version written by this library
+pub const FORMAT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = 12;
+const ENTRY_LEN: usize = 12;
+const KERNING_LEN: usize = 9;
+
+/// An error returned by [`FontBlob::new`] on invalid data
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum FontBlobError {
+    /// The data doesn't start with [`MAGIC`]
+    BadMagic,
+    /// The format version is not supported
+    UnsupportedVersion(u16),
+    /// The data ends before a structure it describes
+    Truncated,
+    /// The table with the index has an invalid char code or glyph addresses
+    InvalidTable(u16),
+    /// The kerning pairs are not sorted or contain an invalid char code
+    InvalidKerning,
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// A table directory entry
+#[derive(Clone,Copy)]
+struct Table {
+    basechar: u32,
+    count: usize,
+    cells: usize,
+    addr: usize,
+}
+
+impl Table {
+    const fn data(&self) -> usize {
+        self.addr + 2 * self.count
+    }
+}
+
+/// A validated font blob, implements [`GlyphProvider`]
+///
+/// See the [module documentation](self) for the format.
+#[derive(Clone,Copy,Debug)]
+pub struct FontBlob<'a> {
+    bytes: &'a [u8],
+    tables: u16,
+    kerning: u32,
+}
+
+impl<'a> FontBlob<'a> {
+    /// Check the blob and wrap it
+    ///
+    /// # Errors
+    /// [`FontBlobError`] if the header, any table or the kerning pairs are invalid
+    pub fn new(bytes: &'a [u8]) -> Result<Self, FontBlobError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(if bytes.len() >= MAGIC.len() && bytes[..MAGIC.len()] != MAGIC {FontBlobError::BadMagic} else {FontBlobError::Truncated});
+        }
+        if bytes[..4] != MAGIC {
+            return Err(FontBlobError::BadMagic);
+        }
+        let version = read_u16(bytes, 4);
+        if version != FORMAT_VERSION {
+            return Err(FontBlobError::UnsupportedVersion(version));
+        }
+
+        let blob = Self{bytes, tables: read_u16(bytes, 6), kerning: read_u32(bytes, 8)};
+        let kerning_end = blob.kerning_start() as u64 + u64::from(blob.kerning) * KERNING_LEN as u64;
+        if kerning_end > bytes.len() as u64 {
+            return Err(FontBlobError::Truncated);
+        }
+
+        for index in 0..blob.tables {
+            let table = blob.table(index);
+            let end = table.addr as u64 + 2 * table.count as u64 + 3 * table.cells as u64;
+            if end > bytes.len() as u64 {
+                return Err(FontBlobError::Truncated);
+            }
+            let chars_valid = table.count > 0
+                && char::from_u32(table.basechar).is_some()
+                && char::from_u32(table.basechar + table.count as u32 - 1).is_some();
+            let mut prev = 0;
+            let addr_valid = (0..table.count).all(|i| {
+                let addr = read_u16(bytes, table.addr + 2 * i);
+                let valid = addr >= prev && usize::from(addr) <= 2 * table.cells;
+                prev = addr;
+                valid
+            });
+            if !chars_valid || !addr_valid {
+                return Err(FontBlobError::InvalidTable(index));
+            }
+        }
+
+        let mut prev: Option<(u32, u32)> = None;
+        for index in 0..blob.kerning as usize {
+            let (left, right, _) = blob.kerning_entry(index);
+            if char::from_u32(left).is_none() || char::from_u32(right).is_none() || prev >= Some((left, right)) {
+                return Err(FontBlobError::InvalidKerning);
+            }
+            prev = Some((left, right));
+        }
+
+        Ok(blob)
+    }
+
+    /// Get the underlying bytes
+    #[must_use]
+    pub const fn bytes(&self) -> &'a [u8] {self.bytes}
+
+    /// Get the number of glyph tables
+    #[must_use]
+    pub const fn table_count(&self) -> u16 {self.tables}
+
+    /// Get the number of kerning pairs
+    #[must_use]
+    pub const fn kerning_count(&self) -> u32 {self.kerning}
+
+    const fn kerning_start(&self) -> usize {
+        HEADER_LEN + ENTRY_LEN * self.tables as usize
+    }
+
+    fn table(&self, index: u16) -> Table {
+        let at = HEADER_LEN + ENTRY_LEN * index as usize;
+        Table {
+            basechar: read_u32(self.bytes, at),
+            count: read_u16(self.bytes, at + 4).into(),
+            cells: read_u16(self.bytes, at + 6).into(),
+            addr: read_u32(self.bytes, at + 8) as usize,
+        }
+    }
+
+    fn kerning_entry(&self, index: usize) -> (u32, u32, i8) {
+        let at = self.kerning_start() + KERNING_LEN * index;
+        (read_u32(self.bytes, at), read_u32(self.bytes, at + 4), self.bytes[at + 8] as i8)
+    }
+}
+
+impl GlyphProvider for FontBlob<'_> {
+    fn get_glyph(self, ch: u32) -> Option<impl Iterator<Item=GlyphStep>> {
+        let table = (0..self.tables).map(|index| self.table(index))
+            .find(|table| ch >= table.basechar && ((ch - table.basechar) as usize) < table.count)?;
+        let idx = (ch - table.basechar) as usize;
+        let start = usize::from(read_u16(self.bytes, table.addr + 2 * idx));
+        let end = if idx + 1 == table.count {2 * table.cells} else {usize::from(read_u16(self.bytes, table.addr + 2 * idx + 2))};
+
+        let data = &self.bytes[table.data()..table.data() + 3 * table.cells];
+        Some((start..end).map(move |step| {
+            let cell = [data[step / 2 * 3], data[step / 2 * 3 + 1], data[step / 2 * 3 + 2]];
+            let [first, second] = GlyphStep::from_gdata(cell);
+            if step % 2 == 0 {first} else {second}
+        }))
+    }
+
+    fn kerning_pair(self, left: char, right: char) -> i8 {
+        let key = (left as u32, right as u32);
+        let (mut lo, mut hi) = (0, self.kerning as usize);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let (l, r, adjustment) = self.kerning_entry(mid);
+            match (l, r).cmp(&key) {
+                core::cmp::Ordering::Less => lo = mid + 1,
+                core::cmp::Ordering::Greater => hi = mid,
+                core::cmp::Ordering::Equal => return adjustment,
+            }
+        }
+        0
+    }
+}
+
+#[test]
+fn test_blob() {
+    let mut blob = [
+        b'D', b'I', b'1', b'6', 1, 0,  1, 0,  2, 0, 0, 0,
+        b'A', 0, 0, 0,  2, 0,  2, 0,  42, 0, 0, 0,
+        b'A', 0, 0, 0,  b'V', 0, 0, 0,  0xFA,
+        b'V', 0, 0, 0,  b'A', 0, 0, 0,  0xFB,
+        0, 0,  3, 0,
+        0x10, 0x50, 0xA2,  0x10, 0x50, 0xA2,
+    ];
+    let font = FontBlob::new(&blob).unwrap();
+    assert_eq!(font.get_glyph('A' as u32).map(Iterator::count), Some(3));
+    assert_eq!(font.get_glyph('B' as u32).map(Iterator::count), Some(1));
+    assert!(font.get_glyph('@' as u32).is_none());
+    assert_eq!(font.kerning_pair('A', 'V'), -6);
+    assert_eq!(font.kerning_pair('V', 'A'), -5);
+    assert_eq!(font.kerning_pair('A', 'A'), 0);
+
+    assert_eq!(FontBlob::new(&blob[..40]).err(), Some(FontBlobError::Truncated));
+    assert_eq!(FontBlob::new(b"DI1").err(), Some(FontBlobError::Truncated));
+    assert_eq!(FontBlob::new(b"PK\x03\x04").err(), Some(FontBlobError::BadMagic));
+
+    blob[44] = 5;
+    assert_eq!(FontBlob::new(&blob).err(), Some(FontBlobError::InvalidTable(0)));
+    blob[44] = 3;
+    blob[33] = b'A';
+    assert_eq!(FontBlob::new(&blob).err(), Some(FontBlobError::InvalidKerning));
+    blob[4] = 2;
+    assert_eq!(FontBlob::new(&blob).err(), Some(FontBlobError::UnsupportedVersion(2)));
+}
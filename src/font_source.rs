@@ -0,0 +1,397 @@
+//! A human-readable font source: compiler and decompiler
+//!
+//! The source is a list of glyph blocks. A block starts with a line with the char
+//! code (`U+0041`, the rest of the line is ignored), followed by the lines of
+//! glyph steps, every step is an opcode and the `x,y` glyph coordinates:
+//!
+//! | Step       | [`GlyphConnectionType`]                          |
+//! |------------|--------------------------------------------------|
+//! | `B x,y`    | `Break`: move to the point                       |
+//! | `L x,y`    | `Outline{thick: true, update: true}`             |
+//! | `l x,y`    | `Outline{thick: false, update: true}`            |
+//! | `P x,y`    | `Outline{thick: true, update: false}`            |
+//! | `p x,y`    | `Outline{thick: false, update: false}`           |
+//! | `C x,y`    | `Control`: the spline control point              |
+//! | `O+ x,y`   | `Oval{right: true}`                              |
+//! | `O- x,y`   | `Oval{right: false}`                             |
+//! | `R U+XXXX` | `Control` + `Break` recalling another glyph      |
+//!
+//! A `kern U+XXXX U+XXXX adjustment` line adds a kerning pair, the text after `#`
+//! is a comment. The steps are packed in pairs, so a compiled table having an odd
+//! total number of steps gets a padding `B 0,0` step after its last glyph.
+//!
+//! ```text
+//! # A
+//! U+0041 A
+//!   B 0,24  L 6,1  L 12,24
+//!   B 3,15  L 9,15
+//! kern U+0041 U+0056 -2
+//! ```
+//!
+//! The [`FontSource`] may be compiled into the Rust [`GlyphTable`] source or the
+//! [`FontBlob`](crate::font_blob::FontBlob) data, and is created from the existing
+//! tables (e.g. [`crate::font_data::TABLES`](font_data/static.TABLES.html)) to edit them.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature="std")] {
+//! # use draw_i16::font_source::*;
+//! let source = FontSource::parse("U+0048 H\n  B 0,1  L 0,24\n  B 12,1  L 12,24\n  B 0,12  L 12,12\n").unwrap();
+//! let blob = source.to_blob();
+//! let font = draw_i16::font_blob::FontBlob::new(&blob).unwrap();
+//! assert_eq!(FontSource::from_provider(font, 'H'..='H').to_text(), source.to_text());
+//! # }
+//! ```
+
+use core::convert::TryFrom;
+use std::fmt::Write;
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+use ranged_integers::Ranged;
+use crate::font::{GlyphConnectionType, GlyphCoord, GlyphProvider, GlyphStep, GlyphTable, code_to_recall, recall_to_code};
+use crate::font_blob::{FORMAT_VERSION, MAGIC};
+
+/// An error in a font source line
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct SourceError {
+    /// The line number, starting from 1
+    pub line: usize,
+    /// What is wrong
+    pub message: &'static str,
+}
+
+impl core::fmt::Display for SourceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// An editable font: the glyphs and the kerning pairs
+#[derive(Clone,PartialEq,Eq,Debug,Default)]
+pub struct FontSource {
+    /// The glyph steps by char, sorted by char
+    pub glyphs: Vec<(char, Vec<GlyphStep>)>,
+    /// The kerning pairs `(left, right, adjustment)` sorted by `(left, right)`
+    pub kerning: Vec<(char, char, i8)>,
+}
+
+fn parse_char(token: &str) -> Option<char> {
+    char::from_u32(parse_code(token)?)
+}
+
+fn parse_code(token: &str) -> Option<u32> {
+    u32::from_str_radix(token.strip_prefix("U+")?, 16).ok()
+}
+
+fn parse_coord(token: &str) -> Option<GlyphCoord> {
+    let (x, y) = token.split_once(',')?;
+    Some(GlyphCoord {
+        x: Ranged::new(x.parse().ok()?)?,
+        y: Ranged::new(y.parse().ok()?)?,
+    })
+}
+
+fn write_coord(out: &mut String, opcode: &str, coord: GlyphCoord) {
+    write!(out, "  {} {},{}", opcode, coord.x.u8(), coord.y.u8()).unwrap();
+}
+
+fn write_steps(out: &mut String, steps: &[GlyphStep]) {
+    let mut i = 0;
+    while i < steps.len() {
+        let GlyphStep{coord, tp} = &steps[i];
+        if *tp == GlyphConnectionType::Break && i > 0 {
+            out.push('\n');
+        }
+        match tp {
+            GlyphConnectionType::Control if matches!(steps.get(i + 1), Some(GlyphStep{tp: GlyphConnectionType::Break, ..})) => {
+                let code = recall_to_code(*coord, steps[i + 1].coord).u32();
+                write!(out, "  R U+{:04X}", code).unwrap();
+                i += 1;
+            }
+            GlyphConnectionType::Control => write_coord(out, "C", *coord),
+            GlyphConnectionType::Break => write_coord(out, "B", *coord),
+            GlyphConnectionType::Oval{right: true} => write_coord(out, "O+", *coord),
+            GlyphConnectionType::Oval{right: false} => write_coord(out, "O-", *coord),
+            GlyphConnectionType::Outline{thick: true, update: true} => write_coord(out, "L", *coord),
+            GlyphConnectionType::Outline{thick: false, update: true} => write_coord(out, "l", *coord),
+            GlyphConnectionType::Outline{thick: true, update: false} => write_coord(out, "P", *coord),
+            GlyphConnectionType::Outline{thick: false, update: false} => write_coord(out, "p", *coord),
+        }
+        i += 1;
+    }
+    out.push('\n');
+}
+
+/// The compiled form of a run of consecutive chars
+struct Table<'s> {
+    basechar: char,
+    addr: Vec<u16>,
+    data: Vec<[u8; 3]>,
+    glyphs: &'s [(char, Vec<GlyphStep>)],
+}
+
+impl FontSource {
+    /// Parse the source text
+    ///
+    /// # Errors
+    /// [`SourceError`] on the first invalid line
+    pub fn parse(source: &str) -> Result<Self, SourceError> {
+        let mut font = Self::default();
+        for (number, line) in source.lines().enumerate() {
+            let error = |message| SourceError{line: number + 1, message};
+            let line = line.split('#').next().unwrap_or_default();
+            let mut tokens = line.split_whitespace();
+            let Some(first) = tokens.next() else {continue};
+
+            if first == "kern" {
+                let left = tokens.next().and_then(parse_char).ok_or_else(|| error("invalid left kerning char"))?;
+                let right = tokens.next().and_then(parse_char).ok_or_else(|| error("invalid right kerning char"))?;
+                let adjustment = tokens.next().and_then(|t| t.parse().ok()).ok_or_else(|| error("invalid kerning adjustment"))?;
+                if font.kerning.iter().any(|pair| (pair.0, pair.1) == (left, right)) {
+                    return Err(error("the kerning pair is defined twice"));
+                }
+                font.kerning.push((left, right, adjustment));
+                continue;
+            }
+            if first.starts_with("U+") {
+                let ch = parse_char(first).ok_or_else(|| error("invalid char code"))?;
+                if font.glyphs.iter().any(|glyph| glyph.0 == ch) {
+                    return Err(error("the char is defined twice"));
+                }
+                font.glyphs.push((ch, Vec::new()));
+                continue;
+            }
+
+            let steps = &mut font.glyphs.last_mut().ok_or_else(|| error("a step outside of a glyph"))?.1;
+            let mut opcode = Some(first);
+            while let Some(op) = opcode {
+                let operand = tokens.next().ok_or_else(|| error("a step without coordinates"))?;
+                if op == "R" {
+                    let code = parse_code(operand).ok_or_else(|| error("invalid recalled char code"))?;
+                    let code = Ranged::new(code.into()).ok_or_else(|| error("the recalled char code is too large"))?;
+                    let (fst, snd) = code_to_recall(code);
+                    steps.push(GlyphStep{coord: fst, tp: GlyphConnectionType::Control});
+                    steps.push(GlyphStep{coord: snd, tp: GlyphConnectionType::Break});
+                }
+                else {
+                    let tp = match op {
+                        "B" => GlyphConnectionType::Break,
+                        "C" => GlyphConnectionType::Control,
+                        "O+" => GlyphConnectionType::Oval{right: true},
+                        "O-" => GlyphConnectionType::Oval{right: false},
+                        "L" => GlyphConnectionType::Outline{thick: true, update: true},
+                        "l" => GlyphConnectionType::Outline{thick: false, update: true},
+                        "P" => GlyphConnectionType::Outline{thick: true, update: false},
+                        "p" => GlyphConnectionType::Outline{thick: false, update: false},
+                        _ => return Err(error("unknown step")),
+                    };
+                    let coord = parse_coord(operand).ok_or_else(|| error("invalid coordinates, expected x,y with x in 0..=15 and y in 0..=31"))?;
+                    steps.push(GlyphStep{coord, tp});
+                }
+                opcode = tokens.next();
+            }
+        }
+
+        font.glyphs.sort_by_key(|glyph| glyph.0);
+        font.kerning.sort_by_key(|&(left, right, _)| (left, right));
+        Ok(font)
+    }
+
+    /// Read the glyphs of the `chars` from a provider
+    ///
+    /// The chars without a glyph are skipped, the kerning pairs are read for
+    /// every pair of the read chars.
+    pub fn from_provider(provider: impl GlyphProvider, chars: impl IntoIterator<Item=char>) -> Self {
+        let glyphs: Vec<(char, Vec<GlyphStep>)> = chars.into_iter()
+            .filter_map(|ch| Some((ch, provider.get_glyph(ch as u32)?.collect())))
+            .collect();
+        let kerning = glyphs.iter()
+            .flat_map(|left| glyphs.iter().map(move |right| (left.0, right.0)))
+            .map(|(left, right)| (left, right, provider.kerning_pair(left, right)))
+            .filter(|pair| pair.2 != 0)
+            .collect();
+        let mut font = Self{glyphs, kerning};
+        font.glyphs.sort_by_key(|glyph| glyph.0);
+        font.glyphs.dedup_by_key(|glyph| glyph.0);
+        font
+    }
+
+    /// Decompile the glyph tables
+    ///
+    /// The tables hold no kerning, the pairs used with [`Kerned`](crate::font::Kerned)
+    /// may be assigned to [`FontSource::kerning`].
+    #[must_use]
+    pub fn from_tables(tables: &[GlyphTable]) -> Self {
+        Self::from_provider(tables, tables.iter().flat_map(|table| {
+            (0..table.addr.len() as u32).filter_map(move |i| char::from_u32(table.basechar as u32 + i))
+        }))
+    }
+
+    /// Write the font as the source text
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (ch, steps) in &self.glyphs {
+            write!(out, "U+{:04X}", *ch as u32).unwrap();
+            if !ch.is_control() && !ch.is_whitespace() {
+                write!(out, " {}", ch).unwrap();
+            }
+            out.push('\n');
+            if !steps.is_empty() {
+                write_steps(&mut out, steps);
+            }
+        }
+        for (left, right, adjustment) in &self.kerning {
+            writeln!(out, "kern U+{:04X} U+{:04X} {}", *left as u32, *right as u32, adjustment).unwrap();
+        }
+        out
+    }
+
+    /// Split the glyphs into the tables of consecutive chars and pack them
+    fn tables(&self) -> Vec<Table<'_>> {
+        let mut tables = Vec::new();
+        let mut start = 0;
+        while start < self.glyphs.len() {
+            let basechar = self.glyphs[start].0 as u32;
+            let len = self.glyphs[start..].iter().enumerate()
+                .take_while(|(i, glyph)| glyph.0 as u32 == basechar + *i as u32)
+                .count();
+            let glyphs = &self.glyphs[start..start + len];
+
+            let mut addr = Vec::new();
+            let mut steps = Vec::new();
+            for (_, glyph) in glyphs {
+                addr.push(u16::try_from(steps.len()).expect("too many steps in a table"));
+                steps.extend(glyph.iter().cloned());
+            }
+            if steps.len() % 2 == 1 {
+                steps.push(GlyphStep{coord: GlyphCoord{x: Ranged::new(0).unwrap(), y: Ranged::new(0).unwrap()}, tp: GlyphConnectionType::Break});
+            }
+            let data = steps.chunks(2).map(|pair| GlyphStep::to_gdata(pair[0].clone(), pair[1].clone())).collect();
+
+            tables.push(Table{basechar: self.glyphs[start].0, addr, data, glyphs});
+            start += len;
+        }
+        tables
+    }
+
+    /// Write the font as the Rust source of `GlyphTable` constants
+    ///
+    /// The tables are named `{name}_0`, `{name}_1` etc., the array of all of them
+    /// is named `{name}` and the kerning pairs for [`Kerned`](crate::font::Kerned)
+    /// are `{name}_KERNING`. The code expects `GlyphTable` in scope.
+    ///
+    /// # Panics
+    /// Panics if a glyph of a table starts beyond the step 65535
+    #[must_use]
+    pub fn to_rust(&self, name: &str) -> String {
+        let tables = self.tables();
+        let mut out = String::new();
+        for (i, table) in tables.iter().enumerate() {
+            writeln!(out, "pub const {}_{}: GlyphTable = GlyphTable {{", name, i).unwrap();
+            writeln!(out, "    basechar: '\\u{{{:x}}}',", table.basechar as u32).unwrap();
+            writeln!(out, "    addr: &{:?},", table.addr).unwrap();
+            writeln!(out, "    data: &[").unwrap();
+            let mut cell = 0;
+            for (ch, steps) in table.glyphs {
+                let cells = (table.addr[(*ch as u32 - table.basechar as u32) as usize] as usize + steps.len()).div_ceil(2);
+                if cell < cells {
+                    write!(out, "        // U+{:04X}\n       ", *ch as u32).unwrap();
+                    for data in &table.data[cell..cells] {
+                        write!(out, " [0x{:02X}, 0x{:02X}, 0x{:02X}],", data[0], data[1], data[2]).unwrap();
+                    }
+                    out.push('\n');
+                    cell = cells;
+                }
+            }
+            writeln!(out, "    ],").unwrap();
+            writeln!(out, "}};").unwrap();
+        }
+        let names: Vec<String> = (0..tables.len()).map(|i| format!("{}_{}", name, i)).collect();
+        writeln!(out, "pub const {}: [GlyphTable; {}] = [{}];", name, tables.len(), names.join(", ")).unwrap();
+        writeln!(out, "pub const {}_KERNING: [(char, char, i8); {}] = {:?};", name, self.kerning.len(), self.kerning).unwrap();
+        out
+    }
+
+    /// Write the font as a [`FontBlob`](crate::font_blob::FontBlob)
+    ///
+    /// # Panics
+    /// Panics if the font doesn't fit the format (more than 65535 tables, chars or step
+    /// pairs in a table, a glyph starting beyond the step 65535, or the kerning pairs
+    /// count or a table offset exceeding `u32`)
+    #[must_use]
+    pub fn to_blob(&self) -> Vec<u8> {
+        let tables = self.tables();
+        let mut out = Vec::new();
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&u16::try_from(tables.len()).expect("too many tables").to_le_bytes());
+        out.extend_from_slice(&u32::try_from(self.kerning.len()).expect("too many kerning pairs").to_le_bytes());
+
+        let mut offset = 12 + 12 * tables.len() + 9 * self.kerning.len();
+        for table in &tables {
+            out.extend_from_slice(&(table.basechar as u32).to_le_bytes());
+            out.extend_from_slice(&u16::try_from(table.addr.len()).expect("too many chars in a table").to_le_bytes());
+            out.extend_from_slice(&u16::try_from(table.data.len()).expect("too many steps in a table").to_le_bytes());
+            out.extend_from_slice(&u32::try_from(offset).expect("the font is too large").to_le_bytes());
+            offset += 2 * table.addr.len() + 3 * table.data.len();
+        }
+        for &(left, right, adjustment) in &self.kerning {
+            out.extend_from_slice(&(left as u32).to_le_bytes());
+            out.extend_from_slice(&(right as u32).to_le_bytes());
+            out.push(adjustment as u8);
+        }
+        for table in &tables {
+            for addr in &table.addr {
+                out.extend_from_slice(&addr.to_le_bytes());
+            }
+            for data in &table.data {
+                out.extend_from_slice(data);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(feature="font_data")]
+#[test]
+fn test_roundtrip() {
+    use crate::font_blob::FontBlob;
+    let tables = &crate::font_data::TABLES[..];
+    let source = FontSource::from_tables(tables);
+    let text = source.to_text();
+    assert_eq!(FontSource::parse(&text), Ok(source.clone()));
+
+    let blob = source.to_blob();
+    let font = FontBlob::new(&blob).unwrap();
+    for ch in ['A', 'ā', 'Ω', 'ё', ' '] {
+        let expected: Vec<u16> = tables.get_glyph(ch as u32).unwrap().map(GlyphStep::into_raw).collect();
+        let actual: Vec<u16> = font.get_glyph(ch as u32).unwrap().map(GlyphStep::into_raw).collect();
+        assert_eq!(actual, expected);
+    }
+
+    assert_eq!(FontSource::parse("B 0,0").err(), Some(SourceError{line: 1, message: "a step outside of a glyph"}));
+    assert_eq!(FontSource::parse("U+0041\n  B 16,0").err().map(|e| e.line), Some(2));
+    assert_eq!(FontSource::parse("U+0041\n  X 1,1").err().map(|e| e.message), Some("unknown step"));
+    assert_eq!(FontSource::parse("kern U+0041 U+0056 -2\nkern U+0041 U+0056 -3").err(), Some(SourceError{line: 2, message: "the kerning pair is defined twice"}));
+    assert_eq!(FontSource::parse("U+0041\nU+0041").err(), Some(SourceError{line: 2, message: "the char is defined twice"}));
+
+    let small = FontSource::parse("U+0041\n  B 0,1  L 6,24\nU+0042\nU+0044\n  R U+0041\n  B 1,1\nkern U+0041 U+0044 -3\n").unwrap();
+    assert_eq!(small.to_text(), "U+0041 A\n  B 0,1  L 6,24\nU+0042 B\nU+0044 D\n  R U+0041\n  B 1,1\nkern U+0041 U+0044 -3\n");
+    assert!(small.to_rust("T").contains("    addr: &[0, 2],\n    data: &[\n        // U+0041\n        [0x10, 0x60, 0xF8],\n    ],\n};"));
+    assert!(small.to_rust("T").contains("pub const T_KERNING: [(char, char, i8); 1] = [('A', 'D', -3)];"));
+    assert_eq!(FontBlob::new(&small.to_blob()).unwrap().kerning_pair('A', 'D'), -3);
+
+    let recall = FontSource::parse("U+0041\n  R U+D800\n  R U+3FFFF\n").unwrap();
+    assert_eq!(recall.to_text(), "U+0041 A\n  R U+D800  R U+3FFFF\n");
+    assert_eq!(FontSource::parse(&recall.to_text()).unwrap().to_text(), recall.to_text());
+    assert_eq!(FontSource::parse("U+0041\n  R U+40000").err().map(|e| e.message), Some("the recalled char code is too large"));
+
+    let long = FontSource{glyphs: vec![('A', vec![small.glyphs[0].1[0].clone(); 65536]), ('B', vec![])], kerning: vec![]};
+    assert!(std::panic::catch_unwind(|| long.to_rust("LONG")).is_err());
+
+    let rust = source.to_rust("GLYPHS");
+    assert!(rust.contains("pub const GLYPHS: [GlyphTable; 4] = [GLYPHS_0, GLYPHS_1, GLYPHS_2, GLYPHS_3];"));
+}
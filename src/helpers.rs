@@ -1,6 +1,7 @@
 //! helpers: the reference-like wrappers for drawables manipulation
 
 use super::{Drawable, V2, v2};
+use crate::canvas::SizeMismatchError;
 
 /// A 2D-reference-like type for `Drawable`, which
 /// points to a window of some Drawable. Using the stencil,
@@ -44,7 +45,9 @@ impl<Colour:Copy> Drawable<Colour> for Stencil<'_, Colour> {
     fn _size(&self) -> V2 {self.size}
 
     fn _clear(&mut self, colour: Colour){
-       self.child.rect_fill(self.origin, self.origin+self.size, colour);
+        if self.size.x > 0 && self.size.y > 0 {
+            self.child.rect_fill(self.origin, self.origin+self.size-v2(1,1), colour);
+        }
     }
     unsafe fn _pixel(&mut self, pos: V2, colour: Colour){
         self.child.pixel(self.origin + pos, colour);
@@ -60,8 +63,326 @@ impl<Colour:Copy> Drawable<Colour> for Stencil<'_, Colour> {
     }
 }
 
-/// A helper for `Rotator` to specify the rotation behaviour
+/// An error returned by [`Clipper::push`] when the clip stack is full
+#[derive(Debug)]
+pub struct ClipStackFull;
+
+/// A clipping wrapper for `Drawable` with a stack of clip rectangles
+///
+/// Unlike [`Stencil`], the `Clipper` doesn't move the origin: the drawing is
+/// made in the child coordinates, and only the pixels inside the current clip
+/// rectangle are forwarded. Every pushed rectangle is intersected with the
+/// previous one, so nested widgets can't draw outside their parents.
+/// The stack holds up to `N` rectangles.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use core::str;
+/// let mut buffer = [b'.'; 2*5];
+/// let mut canvas = Canvas::new(&mut buffer, v2(5,2)).unwrap();
+/// let mut clipper = Clipper::<_, 4>::new(&mut canvas);
+/// clipper.push(v2(1, 0), v2(3, 1)).unwrap();
+/// clipper.push(v2(0, 0), v2(2, 0)).unwrap();
+/// clipper.rect_fill(v2(0, 0), v2(4, 1), b'a');
+/// clipper.pop();
+/// clipper.pixel(v2(3, 1), b'b');
+/// clipper.pixel(v2(4, 1), b'c');
+/// assert_eq!(str::from_utf8(&buffer).unwrap(), ".aa.....b.");
+/// ```
+pub struct Clipper<'a, Colour:Copy, const N: usize> {
+    child: &'a mut dyn Drawable<Colour>,
+    stack: [(V2, V2); N],
+    depth: usize,
+}
+
+impl<'a, Colour:Copy, const N: usize> Clipper<'a, Colour, N> {
+    /// Create a new `Clipper` with the whole `child` area visible
+    pub fn new(child: &'a mut dyn Drawable<Colour>) -> Self {
+        Self{child, stack: [(v2(0, 0), v2(-1, -1)); N], depth: 0}
+    }
+
+    /// Get the current clip rectangle `[p1, p2]`
+    ///
+    /// The rectangle is empty if `p1.x > p2.x` or `p1.y > p2.y`
+    #[must_use]
+    pub fn clip(&self) -> (V2, V2) {
+        if self.depth == 0 {
+            (v2(0, 0), self.child._size() - v2(1, 1))
+        }
+        else {
+            self.stack[self.depth - 1]
+        }
+    }
+
+    /// Restrict the drawing to the intersection of the current clip and `[p1, p2]`
+    ///
+    /// # Errors
+    /// `ClipStackFull` if `N` rectangles are already pushed
+    pub fn push(&mut self, p1: V2, p2: V2) -> Result<(), ClipStackFull> {
+        if self.depth == N {
+            return Err(ClipStackFull);
+        }
+        let (c1, c2) = self.clip();
+        self.stack[self.depth] = (
+            v2(c1.x.max(p1.x.min(p2.x)), c1.y.max(p1.y.min(p2.y))),
+            v2(c2.x.min(p1.x.max(p2.x)), c2.y.min(p1.y.max(p2.y))),
+        );
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Restore the previous clip rectangle, return the removed one
+    pub const fn pop(&mut self) -> Option<(V2, V2)> {
+        if self.depth == 0 {
+            return None;
+        }
+        self.depth -= 1;
+        Some(self.stack[self.depth])
+    }
+}
+
+impl<Colour:Copy, const N: usize> Drawable<Colour> for Clipper<'_, Colour, N> {
+    fn _size(&self) -> V2 {self.child._size()}
+
+    fn _clear(&mut self, colour: Colour) {
+        let (c1, c2) = self.clip();
+        if c1.x <= c2.x && c1.y <= c2.y {
+            unsafe { self.child._rect(c1, c2, colour) }
+        }
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: Colour) {
+        let (c1, c2) = self.clip();
+        if c1.x <= pos.x && pos.x <= c2.x && c1.y <= pos.y && pos.y <= c2.y {
+            self.child._pixel(pos, colour);
+        }
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour) {
+        let (c1, c2) = self.clip();
+        let start = pos.x.max(c1.x);
+        let end = (pos.x + len as i16 - 1).min(c2.x);
+        if c1.y <= pos.y && pos.y <= c2.y && start <= end {
+            self.child._hline(v2(start, pos.y), (end - start + 1) as u16, colour);
+        }
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour) {
+        let (c1, c2) = self.clip();
+        let start = pos.y.max(c1.y);
+        let end = (pos.y + len as i16 - 1).min(c2.y);
+        if c1.x <= pos.x && pos.x <= c2.x && start <= end {
+            self.child._vline(v2(pos.x, start), (end - start + 1) as u16, colour);
+        }
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        let (c1, c2) = self.clip();
+        let r1 = v2(p1.x.max(c1.x), p1.y.max(c1.y));
+        let r2 = v2(p2.x.min(c2.x), p2.y.min(c2.y));
+        if r1.x <= r2.x && r1.y <= r2.y {
+            self.child._rect(r1, r2, colour);
+        }
+    }
+}
+
+/// A source of the [`Masked`] visibility
+pub trait Mask {
+    /// Whether the pixel at `pos` may be drawn
+    fn is_set(&self, pos: V2) -> bool;
+}
+
+impl<F: Fn(V2) -> bool> Mask for F {
+    fn is_set(&self, pos: V2) -> bool {self(pos)}
+}
+
+/// A packed 1 bit per pixel [`Mask`]
+///
+/// Every row occupies `(size.x + 7) / 8` bytes, the most significant bit of
+/// a byte is the leftmost pixel. The pixels outside the mask size are unset.
 #[derive(Clone,Copy)]
+pub struct BitMask<'a> {
+    bits: &'a [u8],
+    size: V2,
+}
+
+impl<'a> BitMask<'a> {
+    /// Create a new mask on the existing buffer
+    ///
+    /// # Errors
+    /// `SizeMismatchError` if the buffer length doesn't match the `size`
+    pub const fn new(bits: &'a [u8], size: V2) -> Result<Self, SizeMismatchError> {
+        if (size.x as usize).div_ceil(8) * size.y as usize == bits.len() {
+            Ok(Self{bits, size})
+        }
+        else {
+            Err(SizeMismatchError)
+        }
+    }
+}
+
+impl Mask for BitMask<'_> {
+    fn is_set(&self, pos: V2) -> bool {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.size.x || pos.y >= self.size.y {
+            return false;
+        }
+        let row = pos.y as usize * (self.size.x as usize).div_ceil(8);
+        self.bits[row + pos.x as usize / 8] & (0x80 >> (pos.x % 8)) != 0
+    }
+}
+
+/// A clipping wrapper for `Drawable` forwarding only the pixels set in a [`Mask`]
+///
+/// The mask is applied in the child coordinates. Lines and rectangles are
+/// split into the runs of set mask pixels, so the child fast paths are kept.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use core::str;
+/// let mut buffer = [b'.'; 2*5];
+/// let mut canvas = Canvas::new(&mut buffer, v2(5,2)).unwrap();
+/// let mask = BitMask::new(&[0b1101_1000, 0b0010_0000], v2(5, 2)).unwrap();
+/// Masked::new(&mut canvas, mask).rect_fill(v2(0, 0), v2(4, 1), b'o');
+/// Masked::new(&mut canvas, |p: V2| p.x == 4).line(v2(4, 0), v2(4, 1), b'x', 1);
+/// assert_eq!(str::from_utf8(&buffer).unwrap(), "oo.ox..o.x");
+/// ```
+pub struct Masked<'a, Colour:Copy, M:Mask> {
+    child: &'a mut dyn Drawable<Colour>,
+    mask: M,
+}
+
+impl<'a, Colour:Copy, M:Mask> Masked<'a, Colour, M> {
+    /// Create a new `Masked` drawing onto `child` where `mask` is set
+    pub fn new(child: &'a mut dyn Drawable<Colour>, mask: M) -> Self {
+        Self{child, mask}
+    }
+}
+
+impl<Colour:Copy, M:Mask> Drawable<Colour> for Masked<'_, Colour, M> {
+    fn _size(&self) -> V2 {self.child._size()}
+
+    fn _clear(&mut self, colour: Colour) {
+        let size = self.child._size();
+        if size.x > 0 && size.y > 0 {
+            unsafe { self._rect(v2(0, 0), size - v2(1, 1), colour) }
+        }
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: Colour) {
+        if self.mask.is_set(pos) {
+            self.child._pixel(pos, colour);
+        }
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour) {
+        let end = pos.x + len as i16;
+        let mut run_start = None;
+        for x in pos.x..=end {
+            let set = x < end && self.mask.is_set(v2(x, pos.y));
+            match (run_start, set) {
+                (None, true) => run_start = Some(x),
+                (Some(start), false) => {
+                    self.child._hline(v2(start, pos.y), (x - start) as u16, colour);
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour) {
+        let end = pos.y + len as i16;
+        let mut run_start = None;
+        for y in pos.y..=end {
+            let set = y < end && self.mask.is_set(v2(pos.x, y));
+            match (run_start, set) {
+                (None, true) => run_start = Some(y),
+                (Some(start), false) => {
+                    self.child._vline(v2(pos.x, start), (y - start) as u16, colour);
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        for y in p1.y..=p2.y {
+            self._hline(v2(p1.x, y), (p2.x - p1.x + 1) as u16, colour);
+        }
+    }
+}
+
+/// A drawable recording the bounding box of everything drawn on it
+///
+/// The probe draws nothing, it has the largest possible size and accepts
+/// any colour type. The drawings at negative coordinates are clipped as usual,
+/// so draw the measured shapes at non-negative positions. See also [`measure`].
+///
+/// # Example
+/// ```
+/// # use draw_i16::*;
+/// let mut probe = BoundsProbe::new();
+/// probe.line(v2(10, 10), v2(20, 5), (), 3);
+/// assert_eq!(probe.bounds(), Some((v2(9, 4), v2(21, 11))));
+/// ```
+#[derive(Clone,Copy,Default)]
+pub struct BoundsProbe {
+    bounds: Option<(V2, V2)>,
+}
+
+impl BoundsProbe {
+    /// Create a probe with nothing recorded
+    #[must_use]
+    pub const fn new() -> Self {
+        Self{bounds: None}
+    }
+
+    /// Get the `[min, max]` corners of the touched pixels, `None` if nothing is drawn
+    #[must_use]
+    pub const fn bounds(&self) -> Option<(V2, V2)> {self.bounds}
+
+    fn touch(&mut self, p1: V2, p2: V2) {
+        self.bounds = Some(match self.bounds {
+            None => (p1, p2),
+            Some((b1, b2)) => (v2(b1.x.min(p1.x), b1.y.min(p1.y)), v2(b2.x.max(p2.x), b2.y.max(p2.y))),
+        });
+    }
+}
+
+impl<Colour:Copy> Drawable<Colour> for BoundsProbe {
+    fn _size(&self) -> V2 {v2(i16::MAX, i16::MAX)}
+
+    fn _clear(&mut self, _colour: Colour) {
+        self.touch(v2(0, 0), v2(i16::MAX - 1, i16::MAX - 1));
+    }
+    unsafe fn _pixel(&mut self, pos: V2, _colour: Colour) {
+        self.touch(pos, pos);
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, _colour: Colour) {
+        self.touch(pos, pos + v2(len as i16 - 1, 0));
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, _colour: Colour) {
+        self.touch(pos, pos + v2(0, len as i16 - 1));
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, _colour: Colour) {
+        self.touch(p1, p2);
+    }
+}
+
+/// Get the `[min, max]` corners of the pixels touched by `draw`
+///
+/// The drawing is performed on a [`BoundsProbe`], `None` is returned if
+/// nothing is drawn.
+///
+/// ```
+/// # use draw_i16::*;
+/// let bounds = measure(|d| d.rect(v2(4, 3), v2(1, 8), ()));
+/// assert_eq!(bounds, Some((v2(1, 3), v2(4, 8))));
+/// ```
+pub fn measure(draw: impl FnOnce(&mut BoundsProbe)) -> Option<(V2, V2)> {
+    let mut probe = BoundsProbe::new();
+    draw(&mut probe);
+    probe.bounds()
+}
+
+/// A helper for `Rotator` to specify the rotation behaviour
+///
+/// The variants cover all the 8 orientations of a rectangle: 4 rotations
+/// and 4 mirrorings. Orientations may be composed with [`RotationType::then`].
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
 pub enum RotationType{
     /// The Rotator has no effect
     NoRotation,
@@ -70,10 +391,103 @@ pub enum RotationType{
     /// Paintings are rotated by 180 degrees
     Flip,
     /// Paintings are rotated by 90 degrees counter-clockwise
-    CCW
+    CCW,
+    /// Paintings are mirrored horizontally (left and right are swapped)
+    MirrorX,
+    /// Paintings are mirrored vertically (top and bottom are swapped)
+    MirrorY,
+    /// Paintings are mirrored over the main diagonal (x and y are swapped)
+    Transpose,
+    /// Paintings are mirrored over the anti-diagonal
+    AntiTranspose,
+}
+
+impl RotationType {
+    /// Get the orientation as (swap x and y, then mirror x, then mirror y)
+    const fn parts(self) -> (bool, bool, bool) {
+        match self {
+            Self::NoRotation => (false, false, false),
+            Self::CW => (true, true, false),
+            Self::Flip => (false, true, true),
+            Self::CCW => (true, false, true),
+            Self::MirrorX => (false, true, false),
+            Self::MirrorY => (false, false, true),
+            Self::Transpose => (true, false, false),
+            Self::AntiTranspose => (true, true, true),
+        }
+    }
+
+    const fn from_parts(parts: (bool, bool, bool)) -> Self {
+        match parts {
+            (false, false, false) => Self::NoRotation,
+            (true, true, false) => Self::CW,
+            (false, true, true) => Self::Flip,
+            (true, false, true) => Self::CCW,
+            (false, true, false) => Self::MirrorX,
+            (false, false, true) => Self::MirrorY,
+            (true, false, false) => Self::Transpose,
+            (true, true, true) => Self::AntiTranspose,
+        }
+    }
+
+    /// Get the orientation as a signed permutation matrix over the centered coordinates
+    const fn matrix(self) -> [[i8; 2]; 2] {
+        let (swap, mx, my) = self.parts();
+        let sx = if mx {-1} else {1};
+        let sy = if my {-1} else {1};
+        if swap {[[0, sx], [sy, 0]]} else {[[sx, 0], [0, sy]]}
+    }
+
+    /// Get the orientation of applying `self` and then `next`
+    ///
+    /// `a.then(b)` draws the same as a `Rotator` with `a` over a `Rotator` with `b`.
+    ///
+    /// ```
+    /// # use draw_i16::*;
+    /// assert_eq!(RotationType::CW.then(RotationType::CW), RotationType::Flip);
+    /// assert_eq!(RotationType::MirrorX.then(RotationType::CW), RotationType::AntiTranspose);
+    /// ```
+    #[must_use]
+    pub const fn then(self, next: Self) -> Self {
+        let (a, b) = (self.matrix(), next.matrix());
+        let m = [
+            [b[0][0]*a[0][0] + b[0][1]*a[1][0], b[0][0]*a[0][1] + b[0][1]*a[1][1]],
+            [b[1][0]*a[0][0] + b[1][1]*a[1][0], b[1][0]*a[0][1] + b[1][1]*a[1][1]],
+        ];
+        if m[0][0] != 0 {Self::from_parts((false, m[0][0] < 0, m[1][1] < 0))}
+        else {Self::from_parts((true, m[0][1] < 0, m[1][0] < 0))}
+    }
+
+    /// Get the orientation cancelling `self`
+    #[must_use]
+    pub const fn inverse(self) -> Self {
+        match self {
+            Self::CW => Self::CCW,
+            Self::CCW => Self::CW,
+            other => other,
+        }
+    }
+
+    /// Whether the orientation swaps the width and the height
+    #[must_use]
+    pub const fn swaps_axes(self) -> bool {self.parts().0}
+
+    /// Map a point of a `Rotator` to its `child_size` child
+    ///
+    /// `rot.inverse().map(p, rotator_size)` maps a child point back to the `Rotator`.
+    pub(crate) const fn map(self, r: V2, child_size: V2) -> V2 {
+        let (swap, mx, my) = self.parts();
+
+        let r = if swap {r.swap()} else {r};
+        v2(
+            if mx {child_size.x-1-r.x} else {r.x},
+            if my {child_size.y-1-r.y} else {r.y},
+        )
+    }
 }
 
-/// Rotator the drawing to its child to either 90, 180 or 270 degrees.
+/// Rotator the drawing to its child to either 90, 180 or 270 degrees,
+/// or mirror it (see [`RotationType`]).
 ///
 /// ```text
 /// Original -> Apply Rotator     ->  Draw on  ->  Result on original
@@ -112,25 +526,27 @@ impl<'a, Colour:Copy> Rotator<'a, Colour> {
     }
 
     fn coord_to_child(&self, r: V2) -> V2 {
-        let sz = self.child._size();
+        self.rot.map(r, self.child._size())
+    }
 
-        match self.rot {
-            RotationType::NoRotation => r,
-            RotationType::CW => v2(sz.x-1-r.y, r.x),
-            RotationType::Flip => v2(sz.x-1-r.x, sz.y-1-r.y),
-            RotationType::CCW => v2(r.y, sz.y-1-r.x),
+    /// Draw a straight line from `p1` to `p2` on the child using its fast paths
+    unsafe fn line_to_child(&mut self, p1: V2, p2: V2, len: u16, colour: Colour) {
+        let (c1, c2) = (self.coord_to_child(p1), self.coord_to_child(p2));
+        if c1.y == c2.y {
+            self.child._hline(v2(c1.x.min(c2.x), c1.y), len, colour);
+        }
+        else {
+            self.child._vline(v2(c1.x, c1.y.min(c2.y)), len, colour);
         }
     }
 }
 impl<Colour:Copy> Drawable<Colour> for Rotator<'_, Colour> {
     fn _size(&self) -> V2 {
-        match self.rot {
-            RotationType::NoRotation|RotationType::Flip => {
-                self.child._size()
-            }
-            RotationType::CW|RotationType::CCW => {
-                self.child._size().swap()
-            }
+        if self.rot.swaps_axes() {
+            self.child._size().swap()
+        }
+        else {
+            self.child._size()
         }
     }
 
@@ -142,48 +558,94 @@ impl<Colour:Copy> Drawable<Colour> for Rotator<'_, Colour> {
     }
 
     unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour){
-
-        match self.rot {
-            RotationType::NoRotation => {
-                self.child._hline(pos, len, colour);
-            }
-            RotationType::CW => {
-                self.child._vline(self.coord_to_child(pos + v2(0, 0)), len, colour);
-            }
-            RotationType::Flip => {
-                self.child._hline(self.coord_to_child(pos + v2(len as i16-1, 0)), len, colour);
-            }
-            RotationType::CCW => {
-                self.child._vline(self.coord_to_child(pos + v2(len as i16-1, 0)), len, colour);
-            }
-        }
+        self.line_to_child(pos, pos + v2(len as i16-1, 0), len, colour);
     }
     unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour){
-        match self.rot {
-            RotationType::NoRotation => {
-                self.child._vline(pos, len, colour);
-            }
-            RotationType::CW => {
-                self.child._hline(self.coord_to_child(pos + v2(0, len as i16-1)), len, colour);
-            }
-            RotationType::Flip => {
-                self.child._vline(self.coord_to_child(pos + v2(0, len as i16-1) ), len, colour);
-            }
-            RotationType::CCW => {
-                self.child._hline(self.coord_to_child(pos), len, colour);
-            }
-        }
+        self.line_to_child(pos, pos + v2(0, len as i16-1), len, colour);
     }
     unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour){
         let pp1 = self.coord_to_child(p1);
         let pp2 = self.coord_to_child(p2);
-        self.child.rect_fill(
-            V2{x:pp1.x, y:pp2.y},
-            V2{x:pp2.x, y:pp1.y},
+        self.child._rect(
+            V2{x:pp1.x.min(pp2.x), y:pp1.y.min(pp2.y)},
+            V2{x:pp1.x.max(pp2.x), y:pp1.y.max(pp2.y)},
             colour);
     }
 }
 
+/// Magnify the drawing to its child: every pixel becomes a block of `factor` pixels
+///
+/// The size of the `Scale` is the child size divided by the factor, the
+/// remainder pixels of the child are left untouched. Lines and rectangles are
+/// forwarded as scaled rectangles, so the child's `_rect` fast path is used.
+///
+/// ```text
+/// Original -> Apply Scale    ->  Draw on  ->  Result on original
+/// drawable    factor: (2,1)      Scale        is magnified
+///
+/// |.....|          |..|          |ab|          |aabb.|
+/// |.....|          |..|          |.c|          |..cc.|
+/// ```
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use core::str;
+/// let mut buffer = [b'.'; 2*5];
+/// let mut canvas = Canvas::new(&mut buffer, v2(5,2)).unwrap();
+/// let mut scaled = Scale::new(&mut canvas, v2(2,1));
+/// assert_eq!(scaled.size(), v2(2,2));
+/// scaled.pixel(v2(0, 0), b'a');
+/// scaled.pixel(v2(1, 0), b'b');
+/// scaled.pixel(v2(1, 1), b'c');
+/// assert_eq!(str::from_utf8(&buffer).unwrap(), "aabb...cc.");
+/// ```
+pub struct Scale<'a, Colour:Copy> {
+    child: &'a mut dyn Drawable<Colour>,
+    factor: V2,
+}
+
+impl<'a, Colour:Copy> Scale<'a, Colour> {
+    /// Create a new `Scale` magnifying by `factor` horizontally and vertically
+    ///
+    /// # Panics
+    /// Panics if a `factor` component is not positive
+    pub fn new(child: &'a mut dyn Drawable<Colour>, factor: V2) -> Self {
+        assert!(factor.x > 0 && factor.y > 0, "scale factor must be positive");
+        Self{child, factor}
+    }
+
+    const fn block_start(&self, pos: V2) -> V2 {
+        v2(pos.x * self.factor.x, pos.y * self.factor.y)
+    }
+}
+
+impl<Colour:Copy> Drawable<Colour> for Scale<'_, Colour> {
+    fn _size(&self) -> V2 {
+        let sz = self.child._size();
+        v2(sz.x / self.factor.x, sz.y / self.factor.y)
+    }
+
+    fn _clear(&mut self, colour: Colour) {
+        let size = self._size();
+        if size.x > 0 && size.y > 0 {
+            unsafe { self._rect(v2(0, 0), size - v2(1, 1), colour) }
+        }
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: Colour) {
+        self._rect(pos, pos, colour);
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour) {
+        self._rect(pos, pos + v2(len as i16 - 1, 0), colour);
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour) {
+        self._rect(pos, pos + v2(0, len as i16 - 1), colour);
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        let end = self.block_start(p2 + v2(1, 1)) - v2(1, 1);
+        self.child._rect(self.block_start(p1), end, colour);
+    }
+}
+
 #[test]
 fn test_rotator() {
     use crate::*;
@@ -195,3 +657,64 @@ fn test_rotator() {
     println!("{}", drawable::canvas_to_string(&canvas));
     assert_eq!(core::str::from_utf8(&buffer).unwrap(), "..cab.");
 }
+
+#[test]
+fn test_orientations() {
+    use crate::*;
+    use RotationType::*;
+    const ALL: [RotationType; 8] = [NoRotation, CW, Flip, CCW, MirrorX, MirrorY, Transpose, AntiTranspose];
+
+    let mut expected = [b'.'; 2*3];
+    let mut buffer = [b'.'; 2*3];
+    for a in ALL {
+        assert_eq!(a.then(a.inverse()), NoRotation);
+        for b in ALL {
+            let mut canvas = Canvas::new(&mut expected, v2(2,3)).unwrap();
+            canvas.clear(b'.');
+            let mut inner = Rotator::new(&mut canvas, b);
+            let mut outer = Rotator::new(&mut inner, a);
+            outer.pixel(v2(0, 0), b'a');
+            outer.line(v2(0, 1), v2(1, 1), b'b', 1);
+            outer.rect_fill(v2(1, 0), v2(1, 0), b'c');
+
+            let mut canvas = Canvas::new(&mut buffer, v2(2,3)).unwrap();
+            canvas.clear(b'.');
+            let mut composed = Rotator::new(&mut canvas, a.then(b));
+            composed.pixel(v2(0, 0), b'a');
+            composed.line(v2(0, 1), v2(1, 1), b'b', 1);
+            composed.rect_fill(v2(1, 0), v2(1, 0), b'c');
+
+            assert_eq!(buffer, expected, "{:?} then {:?}", a, b);
+        }
+    }
+
+    let mut canvas = Canvas::new(&mut buffer, v2(2,3)).unwrap();
+    canvas.clear(b'.');
+    Rotator::new(&mut canvas, MirrorX).pixel(v2(0, 0), b'x');
+    Rotator::new(&mut canvas, MirrorY).pixel(v2(0, 0), b'y');
+    Rotator::new(&mut canvas, Transpose).pixel(v2(2, 0), b't');
+    Rotator::new(&mut canvas, AntiTranspose).line(v2(1, 0), v2(1, 1), b'a', 1);
+    assert_eq!(core::str::from_utf8(&buffer).unwrap(), ".xaat.");
+}
+
+#[test]
+fn test_scale_clear() {
+    use crate::*;
+    let mut buffer = [b'.'; 5];
+    let mut canvas = Canvas::new(&mut buffer, v2(5,1)).unwrap();
+    Scale::new(&mut canvas, v2(2,1)).clear(b'x');
+    Scale::new(&mut canvas, v2(6,1)).clear(b'y');
+    assert_eq!(core::str::from_utf8(&buffer).unwrap(), "xxxx.");
+}
+
+#[test]
+fn test_stencil_clear() {
+    use crate::*;
+    let mut buffer = [b'.'; 4*3];
+    let mut canvas = Canvas::new(&mut buffer, v2(4,3)).unwrap();
+    Stencil::new(&mut canvas, v2(2, 1), v2(0, 0)).clear(b'x');
+    Stencil::new(&mut canvas, v2(2, 1), v2(2, 0)).clear(b'x');
+    Stencil::new(&mut canvas, v2(2, 1), v2(0, 2)).clear(b'x');
+    Stencil::new(&mut canvas, v2(1, 1), v2(2, 1)).clear(b'o');
+    assert_eq!(core::str::from_utf8(&buffer).unwrap(), ".....oo.....");
+}
@@ -0,0 +1,164 @@
+//! Indexed canvas: a drawable of palette indices
+
+use super::{Drawable, V2, v2};
+use crate::canvas::SizeMismatchError;
+
+/// The number of bits per pixel of [`IndexedCanvas`]
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum IndexDepth {
+    /// 16-colour palette, two pixels per byte (the left one in the high nibble)
+    Bits4,
+    /// 256-colour palette, one pixel per byte
+    Bits8,
+}
+
+/// A buffer of palette indices representing a 2D drawing surface
+///
+/// The drawing is performed with palette indices (`u8`), the colours are
+/// looked up at the flush step by [`IndexedCanvas::rows`], so changing the
+/// palette recolours the whole picture without redrawing it.
+///
+/// In [`IndexDepth::Bits4`] mode only the lower 4 bits of an index are stored
+/// and every row occupies `(size.x + 1) / 2` bytes.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*;
+/// let mut buffer = [0; 2*3];
+/// let mut canvas = IndexedCanvas::new(&mut buffer, v2(3,3), IndexDepth::Bits4).unwrap();
+/// canvas.line(v2(0, 0), v2(2, 2), 1, 1);
+///
+/// let palette = [Colour565::BLACK, Colour565::WHITE];
+/// let mut rows = canvas.rows(&palette);
+/// assert!(rows.next().unwrap().eq([Colour565::WHITE, Colour565::BLACK, Colour565::BLACK]));
+/// assert!(rows.next().unwrap().eq([Colour565::BLACK, Colour565::WHITE, Colour565::BLACK]));
+/// ```
+pub struct IndexedCanvas<'buf> {
+    buffer: &'buf mut [u8],
+    size: V2,
+    depth: IndexDepth,
+}
+
+impl<'buf> IndexedCanvas<'buf> {
+    /// Create a new indexed canvas on the existing buffer
+    ///
+    /// The buffer size must be exactly `size.y` rows of [`IndexedCanvas::row_bytes`]
+    ///
+    /// # Errors
+    /// `SizeMismatchError` on invalid canvas size
+    pub const fn new(buffer: &'buf mut [u8], size: V2, depth: IndexDepth) -> Result<Self, SizeMismatchError> {
+        if Self::row_bytes(size.x, depth) * size.y as usize == buffer.len() {
+            Ok(Self { buffer, size, depth })
+        }
+        else {
+            Err(SizeMismatchError)
+        }
+    }
+
+    /// Get the number of bytes a row of `width` pixels occupies
+    #[must_use]
+    pub const fn row_bytes(width: i16, depth: IndexDepth) -> usize {
+        match depth {
+            IndexDepth::Bits4 => (width as usize).div_ceil(2),
+            IndexDepth::Bits8 => width as usize,
+        }
+    }
+
+    /// Get the bits per pixel mode
+    #[must_use]
+    pub const fn depth(&self) -> IndexDepth {self.depth}
+
+    /// Get the raw buffer
+    #[must_use]
+    pub const fn buffer(&self) -> &[u8] {self.buffer}
+
+    /// Get a palette index by coordinates
+    #[must_use]
+    pub fn get_pixel(&self, point: V2) -> Option<u8> {
+        if 0 <= point.x && point.x < self.size.x &&
+           0 <= point.y && point.y < self.size.y
+        {
+            Some(self.index_at(point.x as usize, point.y as usize))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Iterate over the colours of a row using the `palette`
+    ///
+    /// # Panics
+    /// Panics if the row is out of the canvas or an index is out of the `palette`
+    pub fn row<'s, Colour:Copy>(&'s self, y: i16, palette: &'s [Colour]) -> impl Iterator<Item=Colour> + 's {
+        assert!(0 <= y && y < self.size.y);
+        (0..self.size.x as usize).map(move |x| palette[self.index_at(x, y as usize) as usize])
+    }
+
+    /// Iterate over the rows, each row is an iterator over the pixel colours
+    /// looked up in the `palette`
+    ///
+    /// The `palette` should have 16 or 256 entries depending on the [`IndexDepth`].
+    /// The row iterators panic if an index is out of the `palette`
+    pub fn rows<'s, Colour:Copy>(&'s self, palette: &'s [Colour]) -> impl Iterator<Item=impl Iterator<Item=Colour> + 's> + 's {
+        (0..self.size.y).map(move |y| self.row(y, palette))
+    }
+
+    fn index_at(&self, x: usize, y: usize) -> u8 {
+        let row = y * Self::row_bytes(self.size.x, self.depth);
+        match self.depth {
+            IndexDepth::Bits4 => {
+                let byte = self.buffer[row + x/2];
+                if x.is_multiple_of(2) {byte >> 4} else {byte & 0x0F}
+            }
+            IndexDepth::Bits8 => self.buffer[row + x],
+        }
+    }
+
+    fn set_index(&mut self, x: usize, y: usize, index: u8) {
+        let row = y * Self::row_bytes(self.size.x, self.depth);
+        match self.depth {
+            IndexDepth::Bits4 => {
+                let byte = &mut self.buffer[row + x/2];
+                *byte = if x.is_multiple_of(2) {(*byte & 0x0F) | (index << 4)} else {(*byte & 0xF0) | (index & 0x0F)};
+            }
+            IndexDepth::Bits8 => self.buffer[row + x] = index,
+        }
+    }
+}
+
+impl Drawable<u8> for IndexedCanvas<'_> {
+    fn _size(&self) -> V2 {self.size}
+
+    fn _clear(&mut self, colour: u8) {
+        let fill = match self.depth {
+            IndexDepth::Bits4 => (colour & 0x0F) * 0x11,
+            IndexDepth::Bits8 => colour,
+        };
+        for p in self.buffer.iter_mut() {
+            *p = fill;
+        }
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: u8) {
+        self.set_index(pos.x as usize, pos.y as usize, colour);
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: u8) {
+        match self.depth {
+            IndexDepth::Bits4 => {
+                for x in pos.x..(pos.x+len as i16) {
+                    self.set_index(x as usize, pos.y as usize, colour);
+                }
+            }
+            IndexDepth::Bits8 => {
+                let start = pos.x as usize + pos.y as usize * self.size.x as usize;
+                for p in &mut self.buffer[start..start + len as usize] {
+                    *p = colour;
+                }
+            }
+        }
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: u8) {
+        for y in p1.y..=p2.y {
+            self._hline(v2(p1.x, y), (p2.x - p1.x + 1) as u16, colour);
+        }
+    }
+}
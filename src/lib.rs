@@ -25,13 +25,16 @@ The library is `no_std` compatible.
   - Use `()` if only rasterization coordinates are needed, and the colour doesn't matter.
   - Use [`bool`] or 2-variant enum for the displays having black-or-white pixels only.
   - Use [`Colour565`] for 16-bit RGB-colour without alpha channel.
+    [`Colour565Be`], [`Bgr565`] and [`Bgr565Be`] are the same colour in
+    other byte and channel orders, to match the display memory layout.
   - Use [char] or [u8] for ASCII graphics.
   - Use a custom colour (like `[u8; 3]` for rgb) or any data type.
 
 - Create a drawable type by implementing the [`Drawable<ColourType>`] trait for it.
   One needs to define the way to get the canvas size and set a pixel at a point, and optionally
   some speeding-up helper methods. This library contains the [`Canvas`] which
-  implements it as a memory buffer of pixels.
+  implements it as a memory buffer of pixels, and the [`IndexedCanvas`] which
+  stores 4- or 8-bit palette indices.
 
 ```ignore
 struct Lcd;
@@ -78,14 +81,15 @@ display.quad_bezier(v2(10, size.y/2), v2(size.x/2, 0), v2(size.x-10, size.y/2),
 display.quad_bezier(v2(10, size.y/2), v2(size.x/2, size.y), v2(size.x-10, size.y/2), Colour565::GREEN, 1);
 
 // Generate and display text with monospace 7x12 font
+// available under "font_data" feature:
+# #[cfg(feature="font_data")] {
 let text = "Hello";
 let font_size = v2(7, 12);
-let rect_start = size/2 - v2(font_size.x * text.len() as i16 / 2, font_size.y/2);
-let rect_end = size/2 + v2(font_size.x * text.len() as i16 / 2, font_size.y/2);
-display.rect_fill(rect_start, rect_end, Colour565::BLACK);
-# #[cfg(feature="font_data")]
-// available under "font_data" feature:
-display.text(text, font_size, v2(size.x/2 - 7*5/2, size.y/2 - 12/2), Colour565::WHITE);
+let text_size = measure_text(&font_data::TABLES[..], text, font_size);
+let text_start = size/2 - text_size/2;
+display.rect_fill(text_start, text_start + text_size - v2(1, 1), Colour565::BLACK);
+display.text(text, font_size, text_start, Colour565::WHITE);
+# }
 ```
 
 ## How to use fonts
@@ -102,7 +106,10 @@ display.text(text, font_size, v2(size.x/2 - 7*5/2, size.y/2 - 12/2), Colour565::
   [`GlyphProvider`](font::GlyphProvider) trait, which converts
   a characer code to a set of commands for glyph drawing. See the [font] module documentation
   for details. One can use [a gui tool](https://github.com/disiamylborane/draw-i16-fontviewer)
-  to create or edit the font.
+  to create or edit the font. A font stored outside the program (e.g. in an external flash)
+  may be loaded at runtime as a [`FontBlob`](font_blob::FontBlob). The
+  <span class="stab portability" title="Available on crate feature `std` only">`std`</span>
+  feature adds the [`font_source`] text format compiled to both.
 
 - Use the `DrawableMethods.text_with_provider` or `DrawableMethods.symbol_with_provider` functions passing
   the text and a custom [`GlyphProvider`](font::GlyphProvider).
@@ -121,6 +128,9 @@ display.text(text, font_size, v2(size.x/2 - 7*5/2, size.y/2 - 12/2), Colour565::
 #[macro_use]
 extern crate std;
 
+#[cfg(all(any(feature="std", doc), not(test)))]
+extern crate std;
+
 #[macro_use]
 mod v2;
 pub use v2::{V2, v2};
@@ -129,18 +139,42 @@ pub mod drawable;
 pub use drawable::Drawable;
 pub use drawable::DrawableMethods;
 
+pub mod paint;
+
+pub mod record;
+
 pub mod font;
 pub use font::fontsize_to_glyphsize;
 
+pub mod font_blob;
+
+#[cfg(any(feature="std", doc))]
+#[doc(cfg(feature="std"))]
+pub mod font_source;
+
+pub mod text;
+pub use text::measure_text;
+
 #[cfg(any(feature="font_data", doc))]
 #[doc(cfg(feature="font_data"))]
 pub mod font_data;
 
 mod helpers;
+mod transform;
 mod canvas;
 mod colour;
-
-pub use canvas::Canvas;
-pub use colour::Colour565;
-pub use helpers::Stencil;
+mod indexed;
+mod compositor;
+mod strip;
+mod double_buffer;
+
+pub use canvas::{Canvas, SizeMismatchError};
+pub use indexed::{IndexedCanvas, IndexDepth};
+pub use compositor::{Compositor, Layer};
+pub use strip::{Strip, render_strips, strip_height};
+pub use double_buffer::{DoubleBuffer, RowDiff};
+pub use colour::{Colour565, Colour565Be, Bgr565, Bgr565Be, ParseColourError};
+pub use helpers::{Stencil, Scale, Clipper, ClipStackFull, Masked, Mask, BitMask};
+pub use helpers::{BoundsProbe, measure};
 pub use helpers::{Rotator, RotationType};
+pub use transform::{Transform, Affine, FX_ONE, sin_deg, cos_deg};
@@ -0,0 +1,342 @@
+//! Paints: the per-pixel colour sources for filling shapes
+//!
+//! A [`Paint`] yields a colour for every point of the drawable: gradients,
+//! bit patterns, tiled textures or any `Fn(V2) -> Colour`. Wrap a drawable
+//! into a [`Painter`] to draw any shape with a paint instead of a single colour:
+//! the painter is a [`Drawable`] which "colour" is the paint itself, so
+//! `rect_fill`, thick lines, ellipses and glyphs are filled through the same
+//! `_hline`/`_rect` span code.
+//!
+//! # Example
+//! ```
+//! # use draw_i16::*; use draw_i16::paint::*;
+//! let mut buffer = [Colour565::BLACK; 8*2];
+//! let mut canvas = Canvas::new(&mut buffer, v2(8,2)).unwrap();
+//!
+//! let stops = [(0, Colour565::BLACK), (255, Colour565::WHITE)];
+//! let gradient = LinearGradient::new(v2(0, 0), v2(7, 0), &stops);
+//! Painter::new(&mut canvas).rect_fill(v2(0, 0), v2(7, 1), gradient);
+//!
+//! assert_eq!(canvas.get_pixel(v2(0, 1)), Some(Colour565::BLACK));
+//! assert_eq!(canvas.get_pixel(v2(7, 1)), Some(Colour565::WHITE));
+//! ```
+
+use crate::{Colour565, Drawable, V2, v2};
+
+/// A source of pixel colours
+pub trait Paint<Colour:Copy> {
+    /// Get the colour at a point of the drawable
+    fn colour_at(&self, pos: V2) -> Colour;
+}
+
+impl<Colour:Copy, F: Fn(V2) -> Colour> Paint<Colour> for F {
+    fn colour_at(&self, pos: V2) -> Colour {self(pos)}
+}
+
+/// A paint of a single colour
+#[derive(Clone,Copy)]
+pub struct Solid<Colour:Copy>(pub Colour);
+
+impl<Colour:Copy> Paint<Colour> for Solid<Colour> {
+    fn colour_at(&self, _pos: V2) -> Colour {self.0}
+}
+
+/// A drawable wrapper drawing with [`Paint`]s instead of colours
+///
+/// Every span drawn on the painter is split into runs of equal colour,
+/// so the child's `_hline` fast path is kept for the paints
+/// changing slowly along the span.
+pub struct Painter<'a, Colour:Copy> {
+    child: &'a mut dyn Drawable<Colour>,
+}
+
+impl<'a, Colour:Copy> Painter<'a, Colour> {
+    /// Create a new `Painter` drawing onto `child`
+    pub fn new(child: &'a mut dyn Drawable<Colour>) -> Self {
+        Self{child}
+    }
+}
+
+impl<Colour:Copy+PartialEq, P:Paint<Colour>+Copy> Drawable<P> for Painter<'_, Colour> {
+    fn _size(&self) -> V2 {self.child._size()}
+
+    fn _clear(&mut self, paint: P) {
+        let size = self.child._size();
+        if size.x > 0 && size.y > 0 {
+            unsafe { self._rect(v2(0, 0), size - v2(1, 1), paint) }
+        }
+    }
+    unsafe fn _pixel(&mut self, pos: V2, paint: P) {
+        self.child._pixel(pos, paint.colour_at(pos));
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, paint: P) {
+        let end = pos.x + len as i16;
+        let mut start = pos.x;
+        let mut colour = paint.colour_at(pos);
+        for x in pos.x+1..end {
+            let next = paint.colour_at(v2(x, pos.y));
+            if next != colour {
+                self.child._hline(v2(start, pos.y), (x - start) as u16, colour);
+                start = x;
+                colour = next;
+            }
+        }
+        self.child._hline(v2(start, pos.y), (end - start) as u16, colour);
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, paint: P) {
+        for y in p1.y..=p2.y {
+            self._hline(v2(p1.x, y), (p2.x - p1.x + 1) as u16, paint);
+        }
+    }
+}
+
+/// A gradient colour stop: the position along the gradient (0..=255) and the colour
+pub type ColourStop = (u8, Colour565);
+
+/// 4x4 ordered dithering thresholds
+const BAYER: [[u8; 4]; 4] = [
+    [ 0,  8,  2, 10],
+    [12,  4, 14,  6],
+    [ 3, 11,  1,  9],
+    [15,  7, 13,  5],
+];
+
+/// Get the colour of `stops` at the gradient position `t` of 0..=65535
+///
+/// Interpolates in 8-bit channels and optionally applies ordered
+/// dithering at `pos` to hide the RGB565 banding.
+fn stops_colour(stops: &[ColourStop], t: u16, pos: V2, dither: bool) -> Colour565 {
+    let Some(&(first_pos, first)) = stops.first() else {return Colour565::BLACK};
+    let t8 = (t >> 8) as u8;
+    if t8 < first_pos {return first;}
+
+    let (mut a_pos, mut a) = (first_pos, first);
+    for &(b_pos, b) in stops {
+        if t8 < b_pos {
+            let span = u32::from(b_pos - a_pos) << 8;
+            let local = ((u32::from(t) - (u32::from(a_pos) << 8)) << 8) / span;
+            return lerp_dithered(a, b, local, pos, dither);
+        }
+        (a_pos, a) = (b_pos, b);
+    }
+    a
+}
+
+/// Interpolate with the fraction `t` of 0..=256
+fn lerp_dithered(a: Colour565, b: Colour565, t: u32, pos: V2, dither: bool) -> Colour565 {
+    let threshold = if dither {u32::from(BAYER[(pos.y & 3) as usize][(pos.x & 3) as usize])} else {0};
+    let channel = |a: u8, b: u8, step_bits: u32| -> u8 {
+        let (a, b) = (i32::from(a) << 8, i32::from(b) << 8);
+        let value = (a + (b - a) * t as i32 / 256) as u32;
+        // Spread the threshold over one RGB565 step of the channel
+        let value = value + (threshold << (8 + step_bits)) / 16;
+        (value >> 8).min(255) as u8
+    };
+    Colour565::new(channel(a.r(), b.r(), 3), channel(a.g(), b.g(), 2), channel(a.b(), b.b(), 3))
+}
+
+/// A linear gradient between two points
+///
+/// The colour is constant along the lines perpendicular to `from`-`to`
+/// and is extended beyond the end points with the first and the last stops.
+#[derive(Clone,Copy)]
+pub struct LinearGradient<'s> {
+    from: V2,
+    delta: (i64, i64),
+    len2: i64,
+    stops: &'s [ColourStop],
+    dither: bool,
+}
+
+impl<'s> LinearGradient<'s> {
+    /// Create a gradient from `from` to `to` with `stops` sorted by position
+    #[must_use]
+    pub const fn new(from: V2, to: V2, stops: &'s [ColourStop]) -> Self {
+        let delta = (to.x as i64 - from.x as i64, to.y as i64 - from.y as i64);
+        let len2 = delta.0 * delta.0 + delta.1 * delta.1;
+        Self{from, delta, len2, stops, dither: false}
+    }
+
+    /// Enable or disable the ordered dithering
+    #[must_use]
+    pub const fn dithered(self, dither: bool) -> Self {
+        Self{dither, ..self}
+    }
+}
+
+impl Paint<Colour565> for LinearGradient<'_> {
+    fn colour_at(&self, pos: V2) -> Colour565 {
+        let t = if self.len2 == 0 {0} else {
+            let rel = (i64::from(pos.x) - i64::from(self.from.x), i64::from(pos.y) - i64::from(self.from.y));
+            let proj = rel.0 * self.delta.0 + rel.1 * self.delta.1;
+            (proj * 0xFFFF / self.len2).clamp(0, 0xFFFF) as u16
+        };
+        stops_colour(self.stops, t, pos, self.dither)
+    }
+}
+
+/// A radial gradient around a center point
+///
+/// The first stop is at the center, the last one is at the `radius` distance
+/// and beyond.
+#[derive(Clone,Copy)]
+pub struct RadialGradient<'s> {
+    center: V2,
+    radius: u16,
+    stops: &'s [ColourStop],
+    dither: bool,
+}
+
+impl<'s> RadialGradient<'s> {
+    /// Create a gradient around `center` with `stops` sorted by position
+    #[must_use]
+    pub const fn new(center: V2, radius: u16, stops: &'s [ColourStop]) -> Self {
+        Self{center, radius, stops, dither: false}
+    }
+
+    /// Enable or disable the ordered dithering
+    #[must_use]
+    pub const fn dithered(self, dither: bool) -> Self {
+        Self{dither, ..self}
+    }
+}
+
+impl Paint<Colour565> for RadialGradient<'_> {
+    fn colour_at(&self, pos: V2) -> Colour565 {
+        let t = if self.radius == 0 {0xFFFF} else {
+            let rel = (i64::from(pos.x) - i64::from(self.center.x), i64::from(pos.y) - i64::from(self.center.y));
+            let dist2 = (rel.0 * rel.0 + rel.1 * rel.1) as u64;
+            // distance in 8.8 fixed point
+            let dist = (dist2 << 16).isqrt();
+            (dist * 0xFFFF / (u64::from(self.radius) << 8)).min(0xFFFF) as u16
+        };
+        stops_colour(self.stops, t, pos, self.dither)
+    }
+}
+
+/// A two-colour 8x8 bit pattern, tiled from the drawable origin
+///
+/// Every byte of `bits` is a row, the most significant bit is the leftmost pixel.
+/// Set bits are painted with `fg`, unset ones with `bg`. See [`patterns`] for
+/// the common layouts.
+///
+/// ```
+/// # use draw_i16::*; use draw_i16::paint::*;
+/// let mut buffer = [b' '; 4*2];
+/// let mut canvas = Canvas::new(&mut buffer, v2(4,2)).unwrap();
+/// Painter::new(&mut canvas).rect_fill(v2(0, 0), v2(3, 1), Pattern::new(patterns::CHECKER, b'#', b'.'));
+/// assert_eq!(&buffer, b"#.#..#.#");
+/// ```
+#[derive(Clone,Copy)]
+pub struct Pattern<Colour:Copy> {
+    /// Pattern rows
+    pub bits: [u8; 8],
+    /// Colour of the set bits
+    pub fg: Colour,
+    /// Colour of the unset bits
+    pub bg: Colour,
+}
+
+impl<Colour:Copy> Pattern<Colour> {
+    /// Create a new pattern
+    pub const fn new(bits: [u8; 8], fg: Colour, bg: Colour) -> Self {
+        Self{bits, fg, bg}
+    }
+}
+
+impl<Colour:Copy> Paint<Colour> for Pattern<Colour> {
+    fn colour_at(&self, pos: V2) -> Colour {
+        let row = self.bits[(pos.y & 7) as usize];
+        if row & (0x80 >> (pos.x & 7)) != 0 {self.fg} else {self.bg}
+    }
+}
+
+/// Common bit layouts for [`Pattern`]
+pub mod patterns {
+    /// Alternating pixels
+    pub const CHECKER: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA, 0x55];
+    /// Sparse dots, a "disabled" shade
+    pub const SPARSE: [u8; 8] = [0x88, 0x00, 0x22, 0x00, 0x88, 0x00, 0x22, 0x00];
+    /// Horizontal lines
+    pub const HORIZONTAL: [u8; 8] = [0xFF, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00];
+    /// Vertical lines
+    pub const VERTICAL: [u8; 8] = [0x88; 8];
+    /// Diagonal lines going up to the right
+    pub const DIAGONAL: [u8; 8] = [0x11, 0x22, 0x44, 0x88, 0x11, 0x22, 0x44, 0x88];
+    /// Diagonal lines going down to the right
+    pub const BACK_DIAGONAL: [u8; 8] = [0x88, 0x44, 0x22, 0x11, 0x88, 0x44, 0x22, 0x11];
+    /// Both diagonals
+    pub const CROSS_HATCH: [u8; 8] = [0x99, 0x66, 0x66, 0x99, 0x99, 0x66, 0x66, 0x99];
+}
+
+/// An image tiled from the drawable origin
+///
+/// ```
+/// # use draw_i16::*; use draw_i16::paint::*;
+/// let mut buffer = [b' '; 5];
+/// let mut canvas = Canvas::new(&mut buffer, v2(5,1)).unwrap();
+/// let texture = Texture::new(b"ab", v2(2, 1)).unwrap();
+/// Painter::new(&mut canvas).rect_fill(v2(0, 0), v2(4, 0), texture);
+/// assert_eq!(&buffer, b"ababa");
+/// ```
+#[derive(Clone,Copy)]
+pub struct Texture<'a, Colour:Copy> {
+    pixels: &'a [Colour],
+    size: V2,
+}
+
+impl<'a, Colour:Copy> Texture<'a, Colour> {
+    /// Create a texture from the row-major `pixels` of a `size` image
+    ///
+    /// Returns `None` if the `pixels` length is not `size.x * size.y` or the image is empty.
+    #[must_use]
+    pub const fn new(pixels: &'a [Colour], size: V2) -> Option<Self> {
+        if size.x > 0 && size.y > 0 && size.x as usize * size.y as usize == pixels.len() {
+            Some(Self{pixels, size})
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl<Colour:Copy> Paint<Colour> for Texture<'_, Colour> {
+    fn colour_at(&self, pos: V2) -> Colour {
+        let x = pos.x.rem_euclid(self.size.x) as usize;
+        let y = pos.y.rem_euclid(self.size.y) as usize;
+        self.pixels[x + y * self.size.x as usize]
+    }
+}
+
+#[test]
+fn test_gradients() {
+    let stops = [(0, Colour565::BLACK), (128, Colour565::RED), (255, Colour565::WHITE)];
+
+    let linear = LinearGradient::new(v2(0, 0), v2(0, 255), &stops);
+    assert_eq!(linear.colour_at(v2(5, -10)), Colour565::BLACK);
+    assert_eq!(linear.colour_at(v2(5, 64)), Colour565::new(0x78, 0, 0));
+    assert_eq!(linear.colour_at(v2(5, 128)), Colour565::RED);
+    assert_eq!(linear.colour_at(v2(-3, 255)), Colour565::WHITE);
+
+    let radial = RadialGradient::new(v2(10, 10), 20, &stops);
+    assert_eq!(radial.colour_at(v2(10, 10)), Colour565::BLACK);
+    assert_eq!(radial.colour_at(v2(10, 30)), Colour565::WHITE);
+    assert_eq!(radial.colour_at(v2(40, 40)), Colour565::WHITE);
+
+    // The extreme coordinates don't overflow
+    let (min, max) = (v2(i16::MIN, i16::MIN), v2(i16::MAX, i16::MAX));
+    let wide = LinearGradient::new(min, max, &stops);
+    assert_eq!(wide.colour_at(min), Colour565::BLACK);
+    assert_eq!(wide.colour_at(max), Colour565::WHITE);
+    assert_eq!(LinearGradient::new(max, min, &stops).colour_at(max), Colour565::BLACK);
+    assert_eq!(RadialGradient::new(min, u16::MAX, &stops).colour_at(max), Colour565::WHITE);
+    assert_eq!(RadialGradient::new(max, 20, &stops).colour_at(min), Colour565::WHITE);
+
+    // The dithered gradient keeps the average level between the RGB565 steps
+    let grey = [(0, Colour565::new(0x80, 0x80, 0x80)), (255, Colour565::new(0x88, 0x88, 0x88))];
+    let dithered = LinearGradient::new(v2(0, 0), v2(0, 100), &grey).dithered(true);
+    let reds = (0..4).flat_map(|y| (0..4).map(move |x| v2(x, 50+y)))
+        .map(|p| u32::from(dithered.colour_at(p).r()))
+        .sum::<u32>();
+    assert!((0x80*16 + 2*16..0x80*16 + 6*16).contains(&reds));
+}
@@ -0,0 +1,276 @@
+//! Display lists: record the drawing commands once, replay them on any drawable
+//!
+//! A [`Recorder`] is a [`Drawable`] storing every painting into a fixed-capacity
+//! [`DisplayList`] instead of rasterizing it. The shape and text methods of the
+//! recorder store a single high-level [`Command`] each, the `Drawable` primitives
+//! are stored as pixels, lines and rects. [`DisplayList::replay`] paints the
+//! commands onto a drawable, so the same screen may be redrawn after a theme
+//! change, sent to several displays or rendered strip by strip.
+//!
+//! # Example
+//! ```
+//! # use draw_i16::*; use draw_i16::record::*; use draw_i16::font::EmptyGlyphProvider;
+//! let mut list = DisplayList::<u8, EmptyGlyphProvider, 8>::new();
+//! let mut recorder = Recorder::new(&mut list, v2(4, 2));
+//! recorder.clear(b'.');
+//! recorder.line(v2(0, 0), v2(3, 1), b'o', 1);
+//! assert!(!recorder.overflowed());
+//! assert_eq!(list.len(), 2);
+//!
+//! let mut buffer = [b' '; 4*2];
+//! let mut canvas = Canvas::new(&mut buffer, v2(4,2)).unwrap();
+//! list.replay(&mut canvas);
+//! assert_eq!(&buffer, b"oo....oo");
+//! ```
+
+use crate::{Drawable, V2};
+use crate::font::{GlyphProvider, EmptyGlyphProvider};
+
+#[cfg(any(feature="font_data", doc))]
+use crate::font::GlyphTable;
+
+/// A recorded drawing command
+#[derive(Clone,Copy)]
+#[allow(missing_docs)]
+pub enum Command<'a, Colour:Copy, G:GlyphProvider = EmptyGlyphProvider> {
+    /// See [`clear`](crate::DrawableMethods::clear)
+    Clear(Colour),
+    /// See [`pixel`](crate::DrawableMethods::pixel)
+    Pixel{pos: V2, colour: Colour},
+    /// See [`horz_line`](crate::Drawable#method.horz_line)
+    HorzLine{pos: V2, len: u16, colour: Colour},
+    /// See [`vert_line`](crate::Drawable#method.vert_line)
+    VertLine{pos: V2, len: u16, colour: Colour},
+    /// See [`line`](crate::DrawableMethods::line)
+    Line{p1: V2, p2: V2, colour: Colour, width: u8},
+    /// See [`rect`](crate::DrawableMethods::rect)
+    Rect{p1: V2, p2: V2, colour: Colour},
+    /// See [`rect_fill`](crate::DrawableMethods::rect_fill)
+    RectFill{p1: V2, p2: V2, colour: Colour},
+    /// See [`round_rect`](crate::Drawable#method.round_rect)
+    RoundRect{p1: V2, p2: V2, radius: u16, colour: Colour, width: u8},
+    /// See [`ellipse_at_rect`](crate::DrawableMethods::ellipse_at_rect)
+    Ellipse{p1: V2, p2: V2, colour: Colour, quadrants: [bool; 4], width: u8},
+    /// See [`quad_bezier`](crate::DrawableMethods::quad_bezier)
+    QuadBezier{p0: V2, p1: V2, p2: V2, colour: Colour, width: u8},
+    /// See [`quad_spline`](crate::DrawableMethods::quad_spline)
+    QuadSpline{p0: V2, p1: V2, p2: V2, colour: Colour, width: u8},
+    /// See [`symbol_with_provider`](crate::DrawableMethods::symbol_with_provider)
+    Symbol{tables: G, ch: char, fontsize: V2, pos: V2, colour: Colour},
+    /// See [`text_with_provider`](crate::DrawableMethods::text_with_provider)
+    Text{tables: G, s: &'a str, fontsize: V2, pos: V2, colour: Colour},
+}
+
+impl<Colour:Copy, G:GlyphProvider> Command<'_, Colour, G> {
+    /// Paint the command onto a drawable
+    pub fn replay(&self, target: &mut dyn Drawable<Colour>) {
+        match *self {
+            Self::Clear(colour) => target.clear(colour),
+            Self::Pixel{pos, colour} => target.pixel(pos, colour),
+            Self::HorzLine{pos, len, colour} => target.horz_line(pos, len, colour),
+            Self::VertLine{pos, len, colour} => target.vert_line(pos, len, colour),
+            Self::Line{p1, p2, colour, width} => target.line(p1, p2, colour, width),
+            Self::Rect{p1, p2, colour} => target.rect(p1, p2, colour),
+            Self::RectFill{p1, p2, colour} => target.rect_fill(p1, p2, colour),
+            Self::RoundRect{p1, p2, radius, colour, width} => target.round_rect(p1, p2, radius, colour, width),
+            Self::Ellipse{p1, p2, colour, quadrants, width} => target.ellipse_at_rect(p1, p2, colour, quadrants, width),
+            Self::QuadBezier{p0, p1, p2, colour, width} => target.quad_bezier(p0, p1, p2, colour, width),
+            Self::QuadSpline{p0, p1, p2, colour, width} => target.quad_spline(p0, p1, p2, colour, width),
+            Self::Symbol{tables, ch, fontsize, pos, colour} => target.symbol_with_provider(tables, ch, fontsize, pos, colour),
+            Self::Text{tables, s, fontsize, pos, colour} => target.text_with_provider(tables, s, fontsize, pos, colour),
+        }
+    }
+}
+
+/// An error returned by [`DisplayList::push`] when the list is full
+#[derive(Debug)]
+pub struct DisplayListFull;
+
+/// A fixed-capacity list of up to `N` recorded [`Command`]s
+pub struct DisplayList<'a, Colour:Copy, G:GlyphProvider = EmptyGlyphProvider, const N: usize = 64> {
+    commands: [Option<Command<'a, Colour, G>>; N],
+    len: usize,
+}
+
+impl<'a, Colour:Copy, G:GlyphProvider, const N: usize> DisplayList<'a, Colour, G, N> {
+    /// Create an empty list
+    #[must_use]
+    pub const fn new() -> Self {
+        Self{commands: [None; N], len: 0}
+    }
+
+    /// Get the number of the recorded commands
+    #[must_use]
+    pub const fn len(&self) -> usize {self.len}
+
+    /// Whether no commands are recorded
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {self.len == 0}
+
+    /// Remove all the commands
+    pub const fn clear(&mut self) {self.len = 0;}
+
+    /// Append a command
+    ///
+    /// # Errors
+    /// `DisplayListFull` if `N` commands are already recorded
+    pub const fn push(&mut self, command: Command<'a, Colour, G>) -> Result<(), DisplayListFull> {
+        if self.len == N {
+            return Err(DisplayListFull);
+        }
+        self.commands[self.len] = Some(command);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Iterate over the recorded commands
+    pub fn commands(&self) -> impl Iterator<Item=&Command<'a, Colour, G>> {
+        self.commands[..self.len].iter().flatten()
+    }
+
+    /// Paint all the commands onto a drawable
+    pub fn replay(&self, target: &mut dyn Drawable<Colour>) {
+        for command in self.commands() {
+            command.replay(target);
+        }
+    }
+}
+
+impl<Colour:Copy, G:GlyphProvider, const N: usize> Default for DisplayList<'_, Colour, G, N> {
+    fn default() -> Self {Self::new()}
+}
+
+/// A drawable recording the drawing into a [`DisplayList`]
+///
+/// The `size` is reported as the drawable size, it should match the replay targets
+/// for the clipping of the `Drawable` primitives to be the same. If the list
+/// gets full, the following commands are dropped and [`Recorder::overflowed`]
+/// returns `true`.
+pub struct Recorder<'l, 'a, Colour:Copy, G:GlyphProvider = EmptyGlyphProvider, const N: usize = 64> {
+    list: &'l mut DisplayList<'a, Colour, G, N>,
+    size: V2,
+    overflow: bool,
+}
+
+impl<'l, 'a, Colour:Copy, G:GlyphProvider, const N: usize> Recorder<'l, 'a, Colour, G, N> {
+    /// Create a new `Recorder` appending to the `list`
+    ///
+    /// Only the inherent methods of the recorder store single high-level commands.
+    /// Used as a `&mut dyn Drawable` (e.g. by a generic widget code or as a
+    /// [`replay`](DisplayList::replay) target) it receives the rasterized
+    /// primitives, so a single line may take a command per pixel and fill the list
+    /// quickly. Check [`Recorder::overflowed`] after recording.
+    ///
+    /// ```
+    /// # use draw_i16::*; use draw_i16::record::*; use draw_i16::font::EmptyGlyphProvider;
+    /// let mut list = DisplayList::<u8, EmptyGlyphProvider, 8>::new();
+    /// let mut recorder = Recorder::new(&mut list, v2(16, 16));
+    /// recorder.line(v2(0, 0), v2(15, 15), b'o', 1);
+    /// assert_eq!((recorder.overflowed(), list.len()), (false, 1));
+    ///
+    /// let mut list = DisplayList::<u8, EmptyGlyphProvider, 8>::new();
+    /// let mut recorder = Recorder::new(&mut list, v2(16, 16));
+    /// let drawable: &mut dyn Drawable<u8> = &mut recorder;
+    /// drawable.line(v2(0, 0), v2(15, 15), b'o', 1);
+    /// assert!(recorder.overflowed());
+    /// ```
+    pub const fn new(list: &'l mut DisplayList<'a, Colour, G, N>, size: V2) -> Self {
+        Self{list, size, overflow: false}
+    }
+
+    /// Whether some commands were dropped because the list is full
+    #[must_use]
+    pub const fn overflowed(&self) -> bool {self.overflow}
+
+    /// Append a command to the list
+    pub const fn record(&mut self, command: Command<'a, Colour, G>) {
+        if self.list.push(command).is_err() {
+            self.overflow = true;
+        }
+    }
+
+    /// Record a line
+    pub const fn line(&mut self, p1: V2, p2: V2, colour: Colour, width: u8) {
+        self.record(Command::Line{p1, p2, colour, width});
+    }
+
+    /// Record a rectangle contour
+    pub const fn rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        self.record(Command::Rect{p1, p2, colour});
+    }
+
+    /// Record a filled rectangle
+    pub const fn rect_fill(&mut self, p1: V2, p2: V2, colour: Colour) {
+        self.record(Command::RectFill{p1, p2, colour});
+    }
+
+    /// Record a rounded rectangle contour
+    pub const fn round_rect(&mut self, p1: V2, p2: V2, radius: u16, colour: Colour, width: u8) {
+        self.record(Command::RoundRect{p1, p2, radius, colour, width});
+    }
+
+    /// Record an ellipse contour by center and horizontal/vertical radius
+    pub const fn ellipse_at_center(&mut self, center: V2, (a, b): (i16, i16), colour: Colour, quadrants: [bool; 4], width: u8) {
+        let (p1, p2) = (V2::new(center.x - a, center.y - b), V2::new(center.x + a, center.y + b));
+        self.record(Command::Ellipse{p1, p2, colour, quadrants, width});
+    }
+
+    /// Record an ellipse contour by the corner points of the bounding rectangle
+    pub const fn ellipse_at_rect(&mut self, p1: V2, p2: V2, colour: Colour, quadrants: [bool; 4], width: u8) {
+        self.record(Command::Ellipse{p1, p2, colour, quadrants, width});
+    }
+
+    /// Record a quadratic bezier curve
+    pub const fn quad_bezier(&mut self, p0: V2, p1: V2, p2: V2, colour: Colour, width: u8) {
+        self.record(Command::QuadBezier{p0, p1, p2, colour, width});
+    }
+
+    /// Record a quadratic spline
+    pub const fn quad_spline(&mut self, p0: V2, p1: V2, p2: V2, colour: Colour, width: u8) {
+        self.record(Command::QuadSpline{p0, p1, p2, colour, width});
+    }
+
+    /// Record a glyph drawn with the user-defined char code to glyph converter
+    pub const fn symbol_with_provider(&mut self, tables: G, ch: char, fontsize: V2, pos: V2, colour: Colour) {
+        self.record(Command::Symbol{tables, ch, fontsize, pos, colour});
+    }
+
+    /// Record a line of text drawn with the user-defined char code to glyph converter
+    pub const fn text_with_provider(&mut self, tables: G, s: &'a str, fontsize: V2, pos: V2, colour: Colour) {
+        self.record(Command::Text{tables, s, fontsize, pos, colour});
+    }
+}
+
+#[cfg(any(feature="font_data", doc))]
+#[doc(cfg(feature="font_data"))]
+impl<'a, Colour:Copy, const N: usize> Recorder<'_, 'a, Colour, &'static [GlyphTable], N> {
+    /// Record a glyph drawn with the builtin font
+    pub const fn symbol(&mut self, ch: char, fontsize: V2, pos: V2, colour: Colour) {
+        self.symbol_with_provider(&crate::font_data::TABLES, ch, fontsize, pos, colour);
+    }
+
+    /// Record a line of text drawn with the builtin font
+    pub const fn text(&mut self, s: &'a str, fontsize: V2, pos: V2, colour: Colour) {
+        self.text_with_provider(&crate::font_data::TABLES, s, fontsize, pos, colour);
+    }
+}
+
+impl<Colour:Copy, G:GlyphProvider, const N: usize> Drawable<Colour> for Recorder<'_, '_, Colour, G, N> {
+    fn _size(&self) -> V2 {self.size}
+
+    fn _clear(&mut self, colour: Colour) {
+        self.record(Command::Clear(colour));
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: Colour) {
+        self.record(Command::Pixel{pos, colour});
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour) {
+        self.record(Command::HorzLine{pos, len, colour});
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour) {
+        self.record(Command::VertLine{pos, len, colour});
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        self.record(Command::RectFill{p1, p2, colour});
+    }
+}
@@ -0,0 +1,126 @@
+//! Strip rendering: draw a large screen through a small buffer
+
+use super::{Canvas, Drawable, V2, v2};
+
+/// A drawable window onto a horizontal strip of a larger screen
+///
+/// The strip reports the whole `screen` size and accepts the screen coordinates,
+/// but only the rows `[top, top + child height)` are forwarded to the `child`,
+/// moved up by `top`. Use [`Strip::intersects`] to skip the drawings that don't
+/// touch the current strip.
+pub struct Strip<'a, Colour:Copy> {
+    child: &'a mut dyn Drawable<Colour>,
+    top: i16,
+    screen: V2,
+}
+
+impl<'a, Colour:Copy> Strip<'a, Colour> {
+    /// Create a new `Strip` of `screen` starting at the `top` row
+    pub fn new(child: &'a mut dyn Drawable<Colour>, top: i16, screen: V2) -> Self {
+        Self{child, top, screen}
+    }
+
+    /// Get the screen rows covered by the strip, `[top, bottom)`
+    #[must_use]
+    pub fn rows(&self) -> (i16, i16) {
+        (self.top, self.top + self.child._size().y)
+    }
+
+    /// Whether any of the rows `[y1, y2]` are inside the strip
+    #[must_use]
+    pub fn intersects(&self, y1: i16, y2: i16) -> bool {
+        let (top, bottom) = self.rows();
+        y1.min(y2) < bottom && y1.max(y2) >= top
+    }
+}
+
+impl<Colour:Copy> Drawable<Colour> for Strip<'_, Colour> {
+    fn _size(&self) -> V2 {self.screen}
+
+    fn _clear(&mut self, colour: Colour) {
+        self.child._clear(colour);
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: Colour) {
+        if self.intersects(pos.y, pos.y) {
+            self.child._pixel(pos - v2(0, self.top), colour);
+        }
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour) {
+        if self.intersects(pos.y, pos.y) {
+            self.child._hline(pos - v2(0, self.top), len, colour);
+        }
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour) {
+        let (top, bottom) = self.rows();
+        let start = pos.y.max(top);
+        let end = (pos.y + len as i16).min(bottom);
+        if start < end {
+            self.child._vline(v2(pos.x, start - self.top), (end - start) as u16, colour);
+        }
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        let (top, bottom) = self.rows();
+        let y1 = p1.y.max(top);
+        let y2 = p2.y.min(bottom - 1);
+        if y1 <= y2 {
+            self.child._rect(v2(p1.x, y1 - self.top), v2(p2.x, y2 - self.top), colour);
+        }
+    }
+}
+
+/// Get the number of rows of a `width` pixels wide strip fitting in `buffer_len` pixels
+#[must_use]
+pub const fn strip_height(buffer_len: usize, width: i16) -> i16 {
+    if width <= 0 {return 0;}
+    let rows = buffer_len / width as usize;
+    if rows > i16::MAX as usize {i16::MAX} else {rows as i16}
+}
+
+/// Render a `screen` sized picture strip by strip through a small `buffer`
+///
+/// The screen is split into horizontal strips of [`strip_height`] rows (the last
+/// one may be lower). For every strip, `draw` is called to paint the whole
+/// picture in the screen coordinates onto a [`Strip`], and then `flush`
+/// receives the strip top row and its pixels, row by row, to send them to the display.
+///
+/// # Panics
+/// Panics if the `buffer` can't hold a single row of the screen
+///
+/// # Example
+/// ```
+/// # use draw_i16::*;
+/// let mut screen = [b' '; 4*5];
+/// let mut buffer = [0; 4*2];
+/// render_strips(&mut buffer, v2(4, 5),
+///     |strip| {
+///         strip.clear(b'.');
+///         if strip.intersects(1, 3) {
+///             strip.line(v2(1, 1), v2(1, 3), b'o', 1);
+///         }
+///     },
+///     |top, rows| {
+///         let start = top as usize * 4;
+///         screen[start..start + rows.len()].copy_from_slice(rows);
+///     });
+/// assert_eq!(&screen, b".....o...o...o......");
+/// ```
+pub fn render_strips<Colour:Copy>(
+    buffer: &mut [Colour],
+    screen: V2,
+    mut draw: impl FnMut(&mut Strip<Colour>),
+    mut flush: impl FnMut(i16, &[Colour]),
+) {
+    let height = strip_height(buffer.len(), screen.x);
+    assert!(height > 0, "the buffer is smaller than a screen row");
+
+    let mut top = 0;
+    while top < screen.y {
+        let rows = height.min(screen.y - top);
+        let pixels = &mut buffer[..rows as usize * screen.x as usize];
+        if let Ok(mut canvas) = Canvas::new(pixels, v2(screen.x, rows)) {
+            draw(&mut Strip::new(&mut canvas, top, screen));
+        }
+        flush(top, pixels);
+        top += rows;
+    }
+}
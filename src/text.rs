@@ -0,0 +1,595 @@
+//! Text layout: line wrapping and alignment inside a rectangle
+//!
+//! The layout is performed lazily over the `&str` without allocations:
+//! [`TextBox::lines`] splits the text on explicit newlines and wraps the
+//! lines wider than the box at the spaces (or anywhere if a word doesn't fit).
+//!
+//! # Example
+//! ```
+//! # use draw_i16::*; use draw_i16::text::*; use draw_i16::font::EmptyGlyphProvider;
+//! let textbox = TextBox::new(v2(0, 0), v2(29, 99), v2(5, 8));
+//! let lines: Vec<_> = textbox.lines(EmptyGlyphProvider, "Lorem ipsum dolor\nsit amet")
+//!     .map(|line| line.text)
+//!     .collect();
+//! assert_eq!(lines, ["Lorem", "ipsum", "dolor", "sit", "amet"]);
+//! ```
+
+use crate::font::GlyphProvider;
+use crate::{Clipper, Drawable, DrawableMethods, RotationType, Rotator, V2, v2};
+
+/// Horizontal alignment of the text lines
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum HAlign {
+    /// Lines start at the left edge
+    Left,
+    /// Lines are centered
+    Center,
+    /// Lines end at the right edge
+    Right,
+    /// Wrapped lines are stretched to the full width by widening the spaces,
+    /// the paragraph ending lines are aligned left
+    Justify,
+}
+
+/// Vertical alignment of the text block
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum VAlign {
+    /// The first line is at the top edge
+    Top,
+    /// The text block is centered
+    Middle,
+    /// The last line is at the bottom edge
+    Bottom,
+}
+
+/// The text not fitting into the box height
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum Overflow {
+    /// Draw the lines until the bottom edge, the last one may be cut
+    Clip,
+    /// Draw only the fully visible lines, mark the last one with `...`
+    /// if some text is left
+    Ellipsis,
+}
+
+/// A line produced by [`TextBox::lines`]
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct Line<'s> {
+    /// The line text without the surrounding wrap spaces
+    pub text: &'s str,
+    /// Whether the line is ended by wrapping rather than by a newline or the text end
+    pub wrapped: bool,
+}
+
+/// Get the horizontal advance of a char following the `prev` one
+fn char_advance<G:GlyphProvider>(tables: G, prev: Option<char>, ch: char, fontsize: V2) -> i16 {
+    prev.map_or(0, |prev| tables.kerning(prev, ch, fontsize)) + tables.advance(ch, fontsize)
+}
+
+/// Get the width of a single line of text in pixels
+///
+/// The newlines are not handled, see [`measure_text`] for multi-line strings.
+pub fn text_width<G:GlyphProvider>(tables: G, s: &str, fontsize: V2) -> i16 {
+    let mut prev = None;
+    s.chars().map(|c| char_advance(tables, prev.replace(c), c, fontsize)).sum()
+}
+
+/// Size of one line of a text, returned by [`line_metrics`]
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct LineMetrics<'s> {
+    /// The line text without the newline
+    pub text: &'s str,
+    /// Number of chars in the line
+    pub chars: usize,
+    /// Line width in pixels
+    pub width: i16,
+}
+
+/// Iterate over the newline-separated lines of a text with their sizes
+pub fn line_metrics<G:GlyphProvider>(tables: G, s: &str, fontsize: V2) -> impl Iterator<Item=LineMetrics<'_>> {
+    s.split('\n').map(move |text| LineMetrics{text, chars: text.chars().count(), width: text_width(tables, text, fontsize)})
+}
+
+/// Get the size of a text in pixels
+///
+/// The width is the one of the widest line, the height is the line count times `fontsize.y`.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use draw_i16::font::EmptyGlyphProvider;
+/// assert_eq!(measure_text(EmptyGlyphProvider, "Привет\nмир", v2(5, 8)), v2(30, 16));
+/// ```
+pub fn measure_text<G:GlyphProvider>(tables: G, s: &str, fontsize: V2) -> V2 {
+    line_metrics(tables, s, fontsize)
+        .fold(v2(0, 0), |size, line| v2(size.x.max(line.width), size.y + fontsize.y))
+}
+
+/// Direction of a text
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum TextDirection {
+    /// The usual horizontal text
+    LeftToRight,
+    /// The text rotated by 90 degrees clockwise, read top to bottom
+    Rotated90,
+    /// The text rotated by 180 degrees, upside down
+    Rotated180,
+    /// The text rotated by 90 degrees counter-clockwise, read bottom to top
+    Rotated270,
+    /// Upright chars stacked top to bottom, the lines go left to right
+    TopToBottom,
+}
+
+impl TextDirection {
+    /// Get the rotation of the glyphs
+    #[must_use]
+    pub const fn rotation(self) -> RotationType {
+        match self {
+            Self::LeftToRight | Self::TopToBottom => RotationType::NoRotation,
+            Self::Rotated90 => RotationType::CW,
+            Self::Rotated180 => RotationType::Flip,
+            Self::Rotated270 => RotationType::CCW,
+        }
+    }
+}
+
+/// Get the size of a text drawn in the `direction`, see [`text_directed_with_provider`](crate::DrawableMethods::text_directed_with_provider)
+///
+/// # Example
+/// ```
+/// # use draw_i16::*; use draw_i16::text::*; use draw_i16::font::EmptyGlyphProvider;
+/// let fontsize = v2(5, 8);
+/// assert_eq!(measure_text_directed(EmptyGlyphProvider, "abc", fontsize, TextDirection::Rotated90), v2(8, 15));
+/// assert_eq!(measure_text_directed(EmptyGlyphProvider, "abc\nd", fontsize, TextDirection::TopToBottom), v2(10, 24));
+/// ```
+pub fn measure_text_directed<G:GlyphProvider>(tables: G, s: &str, fontsize: V2, direction: TextDirection) -> V2 {
+    if direction == TextDirection::TopToBottom {
+        line_metrics(tables, s, fontsize)
+            .fold(v2(0, 0), |size, line| v2(size.x + fontsize.x, size.y.max(line.chars as i16 * fontsize.y)))
+    }
+    else if direction.rotation().swaps_axes() {
+        measure_text(tables, s, fontsize).swap()
+    }
+    else {
+        measure_text(tables, s, fontsize)
+    }
+}
+
+/// Draw a text in a direction with the top left corner of its bounds at `pos`
+pub(crate) fn draw_directed<Colour:Copy>(drawable: &mut dyn Drawable<Colour>, tables: impl GlyphProvider, s: &str, fontsize: V2, pos: V2, colour: Colour, direction: TextDirection) {
+    if direction == TextDirection::TopToBottom {
+        for (column, line) in s.split('\n').enumerate() {
+            let x = pos.x + column as i16 * fontsize.x;
+            for (row, c) in line.chars().enumerate() {
+                let centering = (fontsize.x - tables.advance(c, fontsize)) / 2;
+                drawable.symbol_with_provider(tables, c, fontsize, v2(x + centering, pos.y + row as i16 * fontsize.y), colour);
+            }
+        }
+        return;
+    }
+
+    let size = measure_text_directed(tables, s, fontsize, direction);
+    let rot = direction.rotation();
+    let mut rotator = Rotator::new(drawable, rot);
+    let rotated_size = rotator.size();
+    let (c1, c2) = (rot.inverse().map(pos, rotated_size), rot.inverse().map(pos + size - v2(1, 1), rotated_size));
+    let origin = v2(c1.x.min(c2.x), c1.y.min(c2.y));
+
+    for (row, line) in s.split('\n').enumerate() {
+        rotator.text_with_provider(tables, line, fontsize, origin + v2(0, row as i16 * fontsize.y), colour);
+    }
+}
+
+/// The pixels of a glyph cell up to 64x64, a bit per pixel
+struct CellMask {
+    rows: [u64; 64],
+    size: V2,
+}
+
+impl Drawable<bool> for CellMask {
+    fn _size(&self) -> V2 {self.size}
+
+    fn _clear(&mut self, colour: bool) {
+        self.rows = [if colour {u64::MAX} else {0}; 64];
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: bool) {
+        self._hline(pos, 1, colour);
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: bool) {
+        let bits = u64::MAX.checked_shr(64 - u32::from(len)).unwrap_or(0) << pos.x;
+        let row = &mut self.rows[pos.y as usize];
+        if colour {*row |= bits} else {*row &= !bits}
+    }
+}
+
+/// Paint the first `width` columns of a cell at `pos` as spans of `fg` ink and `bg`
+fn emit_cell<Colour:Copy>(drawable: &mut dyn Drawable<Colour>, cell: &CellMask, pos: V2, width: i16, fg: Colour, bg: Colour) {
+    if width <= 0 || pos.x >= drawable.size().x || pos.x + width <= 0 {
+        return;
+    }
+    for (dy, &bits) in cell.rows[..cell.size.y as usize].iter().enumerate() {
+        let mut start = 0;
+        while start < width {
+            let ink = bits >> start & 1 != 0;
+            let mut end = start + 1;
+            while end < width && (bits >> end & 1 != 0) == ink {
+                end += 1;
+            }
+            drawable.horz_line(v2(pos.x + start, pos.y + dy as i16), (end - start) as u16, if ink {fg} else {bg});
+            start = end;
+        }
+    }
+}
+
+/// Draw a line of text writing every pixel of its cells once
+///
+/// A cell is painted when the position of the next one is known: the kerned
+/// cells overlapping it get its ink merged in, the gap to a cell moved apart
+/// is filled with `bg`.
+pub(crate) fn draw_with_background<Colour:Copy>(drawable: &mut dyn Drawable<Colour>, tables: impl GlyphProvider, s: &str, fontsize: V2, pos: V2, fg: Colour, bg: Colour) {
+    if !(1..=64).contains(&fontsize.x) || !(1..=64).contains(&fontsize.y) {
+        let size = v2(text_width(tables, s, fontsize), fontsize.y);
+        if size.x > 0 {
+            drawable.rect_fill(pos, pos + size - v2(1, 1), bg);
+        }
+        drawable.text_with_provider(tables, s, fontsize, pos, fg);
+        return;
+    }
+
+    let mut x = pos.x;
+    let mut prev = None;
+    let mut pending: Option<(CellMask, i16)> = None;
+    for c in s.chars() {
+        if let Some(prev) = prev.replace(c) {
+            x += tables.kerning(prev, c, fontsize);
+        }
+        let width = tables.advance(c, fontsize).clamp(0, 64);
+        let mut cell = CellMask{rows: [0; 64], size: v2(width, fontsize.y)};
+        if width > 0 && x < drawable.size().x && x + width > 0 {
+            (&mut cell as &mut dyn Drawable<bool>).symbol_with_provider(tables, c, fontsize, v2(0, 0), true);
+        }
+
+        if let Some((last, last_x)) = pending.take() {
+            let shift = x - last_x;
+            if shift < last.size.x {
+                for (row, &last_row) in cell.rows.iter_mut().zip(last.rows.iter()) {
+                    *row |= if shift >= 0 {last_row.checked_shr(shift as u32)} else {last_row.checked_shl(shift.unsigned_abs().into())}.unwrap_or(0);
+                }
+                cell.size.x = cell.size.x.max(last.size.x - shift).min(64);
+                emit_cell(drawable, &last, v2(last_x, pos.y), shift, fg, bg);
+            }
+            else {
+                emit_cell(drawable, &last, v2(last_x, pos.y), last.size.x, fg, bg);
+                if shift > last.size.x {
+                    drawable.rect_fill(v2(last_x + last.size.x, pos.y), v2(x - 1, pos.y + fontsize.y - 1), bg);
+                }
+            }
+        }
+        pending = Some((cell, x));
+        x += tables.advance(c, fontsize);
+    }
+    if let Some((last, last_x)) = pending {
+        emit_cell(drawable, &last, v2(last_x, pos.y), last.size.x, fg, bg);
+    }
+}
+
+/// The iterator returned by [`TextBox::lines`]
+pub struct Lines<'s, G:GlyphProvider> {
+    rest: Option<&'s str>,
+    width: i16,
+    tables: G,
+    fontsize: V2,
+}
+
+impl<'s, G:GlyphProvider> Iterator for Lines<'s, G> {
+    type Item = Line<'s>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let (para, after) = rest.find('\n').map_or((rest, None), |nl| (&rest[..nl], Some(&rest[nl+1..])));
+
+        let mut used = 0;
+        let mut fit_end = 0;
+        let mut space_break = None;
+        let mut overflow_at = None;
+        let mut prev = None;
+        for (i, c) in para.char_indices() {
+            let advance = char_advance(self.tables, prev.replace(c), c, self.fontsize);
+            if used + advance > self.width {
+                overflow_at = Some((i, c));
+                break;
+            }
+            used += advance;
+            fit_end = i + c.len_utf8();
+            if c == ' ' {
+                space_break = Some(i);
+            }
+        }
+
+        let Some((i, c)) = overflow_at else {
+            self.rest = after;
+            return Some(Line{text: para.trim_end_matches(' '), wrapped: false});
+        };
+
+        let brk = if c == ' ' {i}
+            else if let Some(sp) = space_break.filter(|&sp| !para[..sp].trim_end_matches(' ').is_empty()) {sp}
+            else if fit_end > 0 {fit_end}
+            else {c.len_utf8()};
+
+        self.rest = Some(rest[brk..].trim_start_matches(' '));
+        Some(Line{text: para[..brk].trim_end_matches(' '), wrapped: true})
+    }
+}
+
+/// A rectangle to lay the text out in
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct TextBox {
+    /// Top left corner
+    pub p1: V2,
+    /// Bottom right corner (inclusive)
+    pub p2: V2,
+    /// Size of one char
+    pub fontsize: V2,
+    /// Horizontal alignment
+    pub halign: HAlign,
+    /// Vertical alignment
+    pub valign: VAlign,
+    /// Additional pixels between the lines
+    pub line_spacing: i16,
+    /// What to do with the text not fitting the height
+    pub overflow: Overflow,
+}
+
+impl TextBox {
+    /// Create a top-left aligned, clipping text box `[p1, p2]`
+    #[must_use]
+    pub const fn new(p1: V2, p2: V2, fontsize: V2) -> Self {
+        Self{p1, p2, fontsize, halign: HAlign::Left, valign: VAlign::Top, line_spacing: 0, overflow: Overflow::Clip}
+    }
+
+    /// Set the alignments
+    #[must_use]
+    pub const fn align(self, halign: HAlign, valign: VAlign) -> Self {
+        Self{halign, valign, ..self}
+    }
+
+    /// Set the additional pixels between the lines
+    #[must_use]
+    pub const fn line_spacing(self, line_spacing: i16) -> Self {
+        Self{line_spacing, ..self}
+    }
+
+    /// Set the overflow handling
+    #[must_use]
+    pub const fn overflow(self, overflow: Overflow) -> Self {
+        Self{overflow, ..self}
+    }
+
+    /// Get the box size
+    #[must_use]
+    pub const fn size(&self) -> V2 {
+        v2(self.p2.x - self.p1.x + 1, self.p2.y - self.p1.y + 1)
+    }
+
+    /// Split the text into the lines fitting the box width
+    pub const fn lines<'s, G:GlyphProvider>(&self, tables: G, s: &'s str) -> Lines<'s, G> {
+        Lines{rest: Some(s), width: self.size().x, tables, fontsize: self.fontsize}
+    }
+
+    /// Lay the text out and draw it with the user-defined char code to glyph converter
+    ///
+    /// Nothing is drawn outside the box. See also [`text_box`](crate::DrawableMethods::text_box).
+    pub fn draw_with_provider<Colour:Copy>(&self, drawable: &mut dyn Drawable<Colour>, tables: impl GlyphProvider, s: &str, colour: Colour) {
+        const ELLIPSIS: &str = "...";
+
+        let size = self.size();
+        let pitch = self.fontsize.y + self.line_spacing;
+        let total = self.lines(tables, s).count() as i16;
+        let (shown, truncated) = match self.overflow {
+            Overflow::Clip => (total, false),
+            Overflow::Ellipsis => {
+                let fitting = if pitch > 0 {((size.y + self.line_spacing) / pitch).max(0)} else {total};
+                (total.min(fitting), total > fitting)
+            }
+        };
+
+        let height = shown * self.fontsize.y + (shown - 1).max(0) * self.line_spacing;
+        let mut y = self.p1.y + match self.valign {
+            VAlign::Top => 0,
+            VAlign::Middle => (size.y - height) / 2,
+            VAlign::Bottom => size.y - height,
+        };
+
+        let mut clipper = Clipper::<Colour, 1>::new(drawable);
+        if clipper.push(self.p1, self.p2).is_err() {
+            return;
+        }
+
+        for (idx, line) in self.lines(tables, s).take(shown as usize).enumerate() {
+            let mut text = line.text;
+            let ellipsis = truncated && idx as i16 == shown - 1;
+            if ellipsis {
+                let room = size.x - text_width(tables, ELLIPSIS, self.fontsize);
+                let mut width = 0;
+                let mut prev = None;
+                let end = text.char_indices()
+                    .find(|&(_, c)| {width += char_advance(tables, prev.replace(c), c, self.fontsize); width > room})
+                    .map_or(text.len(), |(i, _)| i);
+                text = text[..end].trim_end_matches(' ');
+            }
+
+            let width = text_width(tables, text, self.fontsize) + if ellipsis {text_width(tables, ELLIPSIS, self.fontsize)} else {0};
+            let justify = self.halign == HAlign::Justify && line.wrapped && !ellipsis;
+            let x = self.p1.x + match self.halign {
+                HAlign::Left | HAlign::Justify => 0,
+                HAlign::Center => (size.x - width) / 2,
+                HAlign::Right => size.x - width,
+            };
+
+            let gaps = if justify {text.chars().filter(|&c| c == ' ').count() as i16} else {0};
+            let extra = if gaps > 0 {size.x - width} else {0};
+
+            let mut pos = v2(x, y);
+            let mut gap = 0;
+            let mut prev = None;
+            for c in text.chars().chain(ELLIPSIS.chars().filter(|_| ellipsis)) {
+                if let Some(prev) = prev.replace(c) {
+                    pos.x += tables.kerning(prev, c, self.fontsize);
+                }
+                clipper.symbol_with_provider(tables, c, self.fontsize, pos, colour);
+                pos.x += tables.advance(c, self.fontsize);
+                if c == ' ' && gaps > 0 {
+                    pos.x += extra / gaps + i16::from(gap < extra % gaps);
+                    gap += 1;
+                }
+            }
+
+            y += pitch;
+        }
+    }
+}
+
+#[test]
+fn test_wrapping() {
+    use crate::font::EmptyGlyphProvider;
+    let textbox = TextBox::new(v2(0, 0), v2(6, 99), v2(1, 1));
+    let lines = |s| textbox.lines(EmptyGlyphProvider, s).map(|l| (l.text, l.wrapped)).collect::<std::vec::Vec<_>>();
+
+    assert_eq!(lines(""), [("", false)]);
+    assert_eq!(lines("a b\n\nc"), [("a b", false), ("", false), ("c", false)]);
+    assert_eq!(lines("one two three"), [("one two", true), ("three", false)]);
+    assert_eq!(lines("abcdefghij k"), [("abcdefg", true), ("hij k", false)]);
+    assert_eq!(lines("  x    yy\n"), [("  x", true), ("yy", false), ("", false)]);
+    assert_eq!(lines("ёжик в тумане"), [("ёжик в", true), ("тумане", false)]);
+}
+
+#[test]
+fn test_alignment() {
+    use crate::font::EmptyGlyphProvider;
+    use crate::measure;
+    let textbox = TextBox::new(v2(10, 10), v2(19, 19), v2(2, 3));
+    let bounds = |textbox: TextBox, s| measure(|d| textbox.draw_with_provider(d, EmptyGlyphProvider, s, 1u8));
+
+    assert_eq!(bounds(textbox, "ab c\nx"), Some((v2(10, 10), v2(16, 15))));
+    assert_eq!(bounds(textbox.align(HAlign::Right, VAlign::Bottom), "ab c\nx"), Some((v2(12, 14), v2(18, 19))));
+    assert_eq!(bounds(textbox.align(HAlign::Center, VAlign::Middle), "ab c\nx"), Some((v2(11, 12), v2(17, 17))));
+    assert_eq!(bounds(textbox, "a b c d e f g h i j k l m").map(|b| b.1.y), Some(19));
+    assert_eq!(bounds(textbox.overflow(Overflow::Ellipsis), "a b c d e f g h i j k l m").map(|b| b.1.y), Some(18));
+}
+
+#[test]
+fn test_kerning() {
+    use crate::font::{GlyphTable, Kerned, Proportional};
+    static TABLES: [GlyphTable; 1] = [GlyphTable{basechar: 'A', addr: &[0; 26], data: &[]}];
+    static PAIRS: [(char, char, i8); 2] = [('A', 'V', -6), ('T', 'o', -3)];
+    let font = Kerned(&TABLES[..], &PAIRS);
+    let fontsize = v2(15, 20);
+
+    assert_eq!(font.kerning_pair('A', 'V'), -6);
+    assert_eq!(font.kerning_pair('V', 'A'), 0);
+    assert_eq!(font.0.kerning('A', 'V', fontsize), 0);
+    assert_eq!(font.kerning('A', 'V', fontsize), -5);
+    assert_eq!(Proportional(font).kerning('A', 'V', fontsize), -5);
+    assert_eq!(Kerned(Proportional(font.0), &PAIRS).kerning('A', 'V', fontsize), -5);
+    assert_eq!(text_width(font.0, "AVA", fontsize), 45);
+    assert_eq!(text_width(font, "AVA", fontsize), 40);
+    assert_eq!(text_width(Proportional(font), "AVA", fontsize), 7 * 3 - 5);
+    assert_eq!(measure_text(Proportional(font), "AV\nVA", fontsize), v2(14, 40));
+}
+
+#[test]
+fn test_zero_weight() {
+    use crate::drawable::canvas_to_string;
+    use crate::font::{EmptyGlyphProvider, TextStyle};
+    let fontsize = v2(9, 16);
+    let style = TextStyle{weight: 0, underline: true, ..TextStyle::REGULAR};
+    assert_eq!(style.margin(fontsize), TextStyle::REGULAR.margin(fontsize));
+
+    let mut regular = [b'.'; 20*18];
+    let mut canvas = crate::Canvas::new(&mut regular, v2(20, 18)).unwrap();
+    canvas.text_styled_with_provider(EmptyGlyphProvider, "ab", fontsize, v2(1, 1), b'#', &TextStyle{weight: 1, ..style});
+    let expected = canvas_to_string(&canvas);
+
+    let mut buffer = [b'.'; 20*18];
+    let mut canvas = crate::Canvas::new(&mut buffer, v2(20, 18)).unwrap();
+    canvas.text_styled_with_provider(EmptyGlyphProvider, "ab", fontsize, v2(1, 1), b'#', &style);
+    assert_eq!(canvas_to_string(&canvas), expected);
+}
+
+#[test]
+fn test_directions() {
+    use crate::font::EmptyGlyphProvider;
+    use crate::measure;
+    let fontsize = v2(6, 8);
+    let pos = v2(20, 30);
+    for direction in [TextDirection::LeftToRight, TextDirection::Rotated90, TextDirection::Rotated180, TextDirection::Rotated270, TextDirection::TopToBottom] {
+        let size = measure_text_directed(EmptyGlyphProvider, "abc\nd", fontsize, direction);
+        let (p1, p2) = measure(|d| d.text_directed_with_provider(EmptyGlyphProvider, "abc\nd", fontsize, pos, 1u8, direction)).unwrap();
+        assert!(p1.x >= pos.x && p1.y >= pos.y, "{:?}", direction);
+        assert!(p2.x < pos.x + size.x && p2.y < pos.y + size.y, "{:?}", direction);
+        assert!(p2.x - p1.x + 1 > size.x * 2 / 3 && p2.y - p1.y + 1 > size.y * 2 / 3, "{:?}", direction);
+    }
+}
+
+#[test]
+fn test_background() {
+    use crate::drawable::canvas_to_string;
+    use crate::font::EmptyGlyphProvider;
+
+    let mut reference = [b' '; 20*12];
+    let mut canvas = crate::Canvas::new(&mut reference, v2(20, 12)).unwrap();
+    canvas.rect_fill(v2(1, 1), v2(18, 10), b'.');
+    canvas.text_with_provider(EmptyGlyphProvider, "ab", v2(9, 10), v2(1, 1), b'#');
+    let expected = canvas_to_string(&canvas);
+
+    let mut buffer = [b' '; 20*12];
+    let mut canvas = crate::Canvas::new(&mut buffer, v2(20, 12)).unwrap();
+    canvas.text_with_background_with_provider(EmptyGlyphProvider, "ab", v2(9, 10), v2(1, 1), b'#', b'.');
+    assert_eq!(canvas_to_string(&canvas), expected);
+}
+
+#[cfg(feature="font_data")]
+#[test]
+fn test_background_proportional() {
+    use crate::drawable::canvas_to_string;
+    use crate::font::Proportional;
+    let font = Proportional(&crate::font_data::TABLES[..]);
+    let text = "Ћиљ Wij ǅ";
+    for fontsize in [v2(4, 6), v2(5, 7), v2(6, 8), v2(7, 10), v2(9, 12)] {
+        let size = measure_text(font, text, fontsize) + v2(2, 2);
+        let mut reference = [b' '; 80*14];
+        let mut canvas = crate::Canvas::new(&mut reference[..(size.x*size.y) as usize], size).unwrap();
+        canvas.rect_fill(v2(1, 1), size - v2(2, 2), b'.');
+        canvas.text_with_provider(font, text, fontsize, v2(1, 1), b'#');
+        let expected = canvas_to_string(&canvas);
+
+        let mut buffer = [b' '; 80*14];
+        let mut canvas = crate::Canvas::new(&mut buffer[..(size.x*size.y) as usize], size).unwrap();
+        canvas.text_with_background_with_provider(font, text, fontsize, v2(1, 1), b'#', b'.');
+        assert_eq!(canvas_to_string(&canvas), expected, "{:?}", fontsize);
+    }
+}
+
+#[cfg(feature="font_data")]
+#[test]
+fn test_background_kerned() {
+    use crate::drawable::canvas_to_string;
+    use crate::font::{Kerned, Proportional};
+    static PAIRS: [(char, char, i8); 2] = [('A', 'V', -4), ('V', 'A', 5)];
+    let tables = &crate::font_data::TABLES[..];
+    for fontsize in [v2(9, 12), v2(14, 20)] {
+        for text in ["AVA", "VAV"] {
+            let font = Kerned(Proportional(tables), &PAIRS);
+            let size = v2(text_width(font, text, fontsize) + 2, fontsize.y + 2);
+            let mut reference = [b'X'; 60*22];
+            let mut canvas = crate::Canvas::new(&mut reference[..(size.x*size.y) as usize], size).unwrap();
+            canvas.rect_fill(v2(1, 1), size - v2(2, 2), b'.');
+            canvas.text_with_provider(font, text, fontsize, v2(1, 1), b'#');
+            let expected = canvas_to_string(&canvas);
+
+            let mut buffer = [b'X'; 60*22];
+            let mut canvas = crate::Canvas::new(&mut buffer[..(size.x*size.y) as usize], size).unwrap();
+            canvas.text_with_background_with_provider(font, text, fontsize, v2(1, 1), b'#', b'.');
+            assert_eq!(canvas_to_string(&canvas), expected, "{:?} {}", fontsize, text);
+        }
+    }
+}
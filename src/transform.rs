@@ -0,0 +1,334 @@
+//! Affine transform: scale, shear, rotate and translate the drawing
+
+use super::{Drawable, V2, v2};
+
+/// Fixed-point 1.0 of [`Affine`] coefficients (16.16)
+pub const FX_ONE: i32 = 1 << 16;
+
+/// `sin` of 0..=90 degrees in 16.16 fixed point
+const SIN_TABLE: [i32; 91] = [
+    0, 1144, 2287, 3430, 4572, 5712, 6850, 7987, 9121, 10252,
+    11380, 12505, 13626, 14742, 15855, 16962, 18064, 19161, 20252, 21336,
+    22415, 23486, 24550, 25607, 26656, 27697, 28729, 29753, 30767, 31772,
+    32768, 33754, 34729, 35693, 36647, 37590, 38521, 39441, 40348, 41243,
+    42126, 42995, 43852, 44695, 45525, 46341, 47143, 47930, 48703, 49461,
+    50203, 50931, 51643, 52339, 53020, 53684, 54332, 54963, 55578, 56175,
+    56756, 57319, 57865, 58393, 58903, 59396, 59870, 60326, 60764, 61183,
+    61584, 61966, 62328, 62672, 62997, 63303, 63589, 63856, 64104, 64332,
+    64540, 64729, 64898, 65048, 65177, 65287, 65376, 65446, 65496, 65526,
+    65536,
+];
+
+/// `sin` of an angle in degrees, 16.16 fixed point
+#[must_use]
+pub const fn sin_deg(deg: i16) -> i32 {
+    let deg = deg.rem_euclid(360);
+    match deg {
+        0..=90 => SIN_TABLE[deg as usize],
+        91..=180 => SIN_TABLE[(180 - deg) as usize],
+        181..=270 => -SIN_TABLE[(deg - 180) as usize],
+        _ => -SIN_TABLE[(360 - deg) as usize],
+    }
+}
+
+/// `cos` of an angle in degrees, 16.16 fixed point
+#[must_use]
+pub const fn cos_deg(deg: i16) -> i32 {
+    sin_deg((deg % 360) + 90)
+}
+
+/// A 2x3 affine matrix with 16.16 fixed-point coefficients
+///
+/// A point is mapped as
+/// ```text
+/// x' = a*x + b*y + tx
+/// y' = c*x + d*y + ty
+/// ```
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct Affine {
+    /// x by x coefficient
+    pub a: i32,
+    /// x by y coefficient
+    pub b: i32,
+    /// y by x coefficient
+    pub c: i32,
+    /// y by y coefficient
+    pub d: i32,
+    /// x translation
+    pub tx: i32,
+    /// y translation
+    pub ty: i32,
+}
+
+impl Affine {
+    /// The transform having no effect
+    pub const IDENTITY: Self = Self{a: FX_ONE, b: 0, c: 0, d: FX_ONE, tx: 0, ty: 0};
+
+    /// Move by `offset` pixels
+    #[must_use]
+    pub const fn translate(offset: V2) -> Self {
+        Self{tx: offset.x as i32 * FX_ONE, ty: offset.y as i32 * FX_ONE, ..Self::IDENTITY}
+    }
+
+    /// Scale by 16.16 fixed-point factors (`FX_ONE` keeps the size)
+    #[must_use]
+    pub const fn scale(sx: i32, sy: i32) -> Self {
+        Self{a: sx, d: sy, ..Self::IDENTITY}
+    }
+
+    /// Shear by 16.16 fixed-point factors: `x += kx*y`, `y += ky*x`
+    ///
+    /// A negative `kx` around `-FX_ONE/4` gives an italic-like slant.
+    #[must_use]
+    pub const fn shear(kx: i32, ky: i32) -> Self {
+        Self{b: kx, c: ky, ..Self::IDENTITY}
+    }
+
+    /// Rotate clockwise (on the y-down screen) by `deg` degrees around the origin
+    #[must_use]
+    pub const fn rotate_deg(deg: i16) -> Self {
+        let (sin, cos) = (sin_deg(deg), cos_deg(deg));
+        Self{a: cos, b: -sin, c: sin, d: cos, tx: 0, ty: 0}
+    }
+
+    /// Rotate clockwise by `deg` degrees around the center of the `center` pixel
+    #[must_use]
+    pub const fn rotate_around(deg: i16, center: V2) -> Self {
+        let cx = center.x as i32 * FX_ONE + FX_ONE/2;
+        let cy = center.y as i32 * FX_ONE + FX_ONE/2;
+        Self{tx: -cx, ty: -cy, ..Self::IDENTITY}
+            .then(Self::rotate_deg(deg))
+            .then(Self{tx: cx, ty: cy, ..Self::IDENTITY})
+    }
+
+    /// Get the transform applying `self` first and then `next`
+    #[must_use]
+    pub const fn then(self, next: Self) -> Self {
+        const fn mul(a: i32, b: i32, c: i32, d: i32) -> i32 {
+            ((a as i64 * b as i64 + c as i64 * d as i64) >> 16) as i32
+        }
+        Self {
+            a: mul(next.a, self.a, next.b, self.c),
+            b: mul(next.a, self.b, next.b, self.d),
+            c: mul(next.c, self.a, next.d, self.c),
+            d: mul(next.c, self.b, next.d, self.d),
+            tx: mul(next.a, self.tx, next.b, self.ty) + next.tx,
+            ty: mul(next.c, self.tx, next.d, self.ty) + next.ty,
+        }
+    }
+
+    /// Map a 16.16 fixed-point point
+    #[must_use]
+    pub const fn apply_fx(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (
+            (((self.a as i64 * x as i64 + self.b as i64 * y as i64) >> 16) as i32).saturating_add(self.tx),
+            (((self.c as i64 * x as i64 + self.d as i64 * y as i64) >> 16) as i32).saturating_add(self.ty),
+        )
+    }
+
+    /// Map a pixel: the result is the pixel containing the transformed pixel center
+    #[must_use]
+    pub const fn apply(&self, p: V2) -> V2 {
+        let (x, y) = self.apply_fx((p.x as i32 * FX_ONE + FX_ONE/2, p.y as i32 * FX_ONE + FX_ONE/2));
+        v2(x.div_euclid(FX_ONE) as i16, y.div_euclid(FX_ONE) as i16)
+    }
+
+    /// Get the average linear scale factor, 16.16 fixed point
+    #[must_use]
+    pub const fn linear_scale(&self) -> i32 {
+        let det = (self.a as i64 * self.d as i64 - self.b as i64 * self.c as i64).unsigned_abs();
+        det.isqrt() as i32
+    }
+}
+
+/// Transform the drawing to its child with an [`Affine`] matrix
+///
+/// The `Drawable` primitives are mapped as areas: every pixel, line or rect
+/// becomes a filled quadrilateral on the child, so the scaled or rotated shapes
+/// stay continuous. The shape methods defined on `Transform` itself
+/// ([`line`](Self::line), [`quad_bezier`](Self::quad_bezier), [`rect_fill`](Self::rect_fill)...)
+/// map the vertices instead and rasterize the shape on the child, so a rotated line
+/// is as thin and smooth as a regular one.
+///
+/// The transform surface is virtually unbounded in the positive direction;
+/// the `Drawable` primitives drawn at negative coordinates are clipped,
+/// the vertex-mapping methods accept any coordinates.
+///
+/// # Example
+/// ```
+/// # use draw_i16::*;
+/// let mut buffer = [b'.'; 4*4];
+/// let mut canvas = Canvas::new(&mut buffer, v2(4,4)).unwrap();
+/// let mut scaled = Transform::new(&mut canvas, Affine::scale(2*FX_ONE, 2*FX_ONE));
+/// scaled.pixel(v2(1, 0), b'o');
+/// assert_eq!(core::str::from_utf8(&buffer).unwrap(), "..oo..oo........");
+/// ```
+pub struct Transform<'a, Colour:Copy> {
+    child: &'a mut dyn Drawable<Colour>,
+    matrix: Affine,
+}
+
+impl<'a, Colour:Copy> Transform<'a, Colour> {
+    /// Create a new `Transform` drawing onto `child` through the `matrix`
+    pub fn new(child: &'a mut dyn Drawable<Colour>, matrix: Affine) -> Self {
+        Self{child, matrix}
+    }
+
+    /// Map a point to the child drawable
+    #[must_use]
+    pub const fn map(&self, p: V2) -> V2 {self.matrix.apply(p)}
+
+    fn map_width(&self, width: u8) -> u8 {
+        if width == 0 {return 0;}
+        let w = (i64::from(width) * i64::from(self.matrix.linear_scale()) + i64::from(FX_ONE/2)) >> 16;
+        w.clamp(1, 255) as u8
+    }
+
+    /// Draw a line by mapping its ends, the width is scaled too
+    pub fn line(&mut self, p1: V2, p2: V2, colour: Colour, width: u8) {
+        let width = self.map_width(width);
+        self.child.line(self.map(p1), self.map(p2), colour, width);
+    }
+
+    /// Draw a rectangle contour as 4 mapped lines
+    pub fn rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        let (c1, c2, c3, c4) = (self.map(p1), self.map(v2(p2.x, p1.y)), self.map(p2), self.map(v2(p1.x, p2.y)));
+        let width = self.map_width(1);
+        self.child.line(c1, c2, colour, width);
+        self.child.line(c2, c3, colour, width);
+        self.child.line(c3, c4, colour, width);
+        self.child.line(c4, c1, colour, width);
+    }
+
+    /// Draw a filled rectangle as a mapped quadrilateral
+    pub fn rect_fill(&mut self, p1: V2, p2: V2, colour: Colour) {
+        let (x1, x2) = (p1.x.min(p2.x), p1.x.max(p2.x));
+        let (y1, y2) = (p1.y.min(p2.y), p1.y.max(p2.y));
+        self.fill_area(v2(x1, y1), (i32::from(x2) + 1, i32::from(y2) + 1), colour);
+    }
+
+    /// Draw a quadratic bezier curve by mapping its control points
+    pub fn quad_bezier(&mut self, p0: V2, p1: V2, p2: V2, colour: Colour, width: u8) {
+        let width = self.map_width(width);
+        self.child.quad_bezier(self.map(p0), self.map(p1), self.map(p2), colour, width);
+    }
+
+    /// Draw a quadratic spline by mapping its points
+    pub fn quad_spline(&mut self, p0: V2, p1: V2, p2: V2, colour: Colour, width: u8) {
+        let width = self.map_width(width);
+        self.child.quad_spline(self.map(p0), self.map(p1), self.map(p2), colour, width);
+    }
+
+    /// Fill the mapped area of pixels from `p1` up to the `end` corner (exclusive)
+    fn fill_area(&mut self, p1: V2, end: (i32, i32), colour: Colour) {
+        let corner = |x: i32, y: i32| self.matrix.apply_fx((x.saturating_mul(FX_ONE), y.saturating_mul(FX_ONE)));
+        let (x1, y1) = (i32::from(p1.x), i32::from(p1.y));
+        let quad = [
+            corner(x1, y1),
+            corner(end.0, y1),
+            corner(end.0, end.1),
+            corner(x1, end.1),
+        ];
+        fill_convex_fx(self.child, &quad, colour);
+    }
+}
+
+/// Get the index of the first pixel which center is at or after the fixed-point `v`
+const fn first_center_at(v: i64) -> i64 {
+    -((FX_ONE as i64/2 - v).div_euclid(FX_ONE as i64))
+}
+
+/// Fill a convex polygon with 16.16 fixed-point vertices, covering the pixels
+/// which centers are inside
+fn fill_convex_fx<Colour:Copy>(drawable: &mut dyn Drawable<Colour>, points: &[(i32, i32)], colour: Colour) {
+    let size = drawable.size();
+    let ymin = points.iter().map(|p| p.1).min().unwrap_or(0);
+    let ymax = points.iter().map(|p| p.1).max().unwrap_or(0);
+    let rows = first_center_at(i64::from(ymin)).max(0)..first_center_at(i64::from(ymax)).min(i64::from(size.y));
+
+    for row in rows {
+        let yc = row * i64::from(FX_ONE) + i64::from(FX_ONE/2);
+        let mut span: Option<(i64, i64)> = None;
+        for (i, &(px, py)) in points.iter().enumerate() {
+            let (qx, qy) = points[(i + 1) % points.len()];
+            let (px, py, qx, qy) = (i64::from(px), i64::from(py), i64::from(qx), i64::from(qy));
+            if py == qy || yc < py.min(qy) || yc > py.max(qy) {
+                continue;
+            }
+            let x = px + (qx - px) * (yc - py) / (qy - py);
+            span = Some(span.map_or((x, x), |(l, r)| (l.min(x), r.max(x))));
+        }
+        if let Some((l, r)) = span {
+            let first = first_center_at(l).max(-1);
+            let end = first_center_at(r).min(i64::from(size.x) + 1);
+            if end > first {
+                drawable.horz_line(v2(first as i16, row as i16), (end - first) as u16, colour);
+            }
+        }
+    }
+}
+
+impl<Colour:Copy> Drawable<Colour> for Transform<'_, Colour> {
+    fn _size(&self) -> V2 {v2(i16::MAX, i16::MAX)}
+
+    fn _clear(&mut self, colour: Colour) {
+        self.child._clear(colour);
+    }
+    unsafe fn _pixel(&mut self, pos: V2, colour: Colour) {
+        self.fill_area(pos, (i32::from(pos.x) + 1, i32::from(pos.y) + 1), colour);
+    }
+    unsafe fn _hline(&mut self, pos: V2, len: u16, colour: Colour) {
+        self.fill_area(pos, (i32::from(pos.x) + i32::from(len), i32::from(pos.y) + 1), colour);
+    }
+    unsafe fn _vline(&mut self, pos: V2, len: u16, colour: Colour) {
+        self.fill_area(pos, (i32::from(pos.x) + 1, i32::from(pos.y) + i32::from(len)), colour);
+    }
+    unsafe fn _rect(&mut self, p1: V2, p2: V2, colour: Colour) {
+        self.fill_area(p1, (i32::from(p2.x) + 1, i32::from(p2.y) + 1), colour);
+    }
+}
+
+#[test]
+fn test_transform() {
+    use crate::{Canvas, DrawableMethods};
+
+    assert_eq!(Affine::rotate_deg(90).apply(v2(3, 1)), v2(-2, 3));
+    assert_eq!(Affine::rotate_around(180, v2(5, 5)).apply(v2(4, 2)), v2(6, 8));
+    assert_eq!(Affine::translate(v2(1, 2)).then(Affine::scale(2*FX_ONE, FX_ONE)).apply(v2(0, 0)), v2(3, 2));
+
+    let mut buffer = [b'.'; 5*5];
+    let mut canvas = Canvas::new(&mut buffer, v2(5,5)).unwrap();
+    let mut rotated = Transform::new(&mut canvas, Affine::rotate_around(90, v2(2, 2)));
+    rotated.rect_fill(v2(0, 0), v2(2, 0), b'a');
+    rotated.pixel(v2(4, 4), b'b');
+    rotated.line(v2(1, 4), v2(3, 4), b'c', 1);
+    println!("{}", crate::drawable::canvas_to_string(&canvas));
+    assert_eq!(core::str::from_utf8(&buffer).unwrap(),
+        concat!(
+        "....a",
+        "c...a",
+        "c...a",
+        "c....",
+        "b...."));
+
+    // A rotated thin stroke stays continuous
+    let mut buffer = [b'.'; 16*16];
+    let mut canvas = Canvas::new(&mut buffer, v2(16,16)).unwrap();
+    let mut rotated = Transform::new(&mut canvas, Affine::rotate_around(30, v2(8, 8)));
+    DrawableMethods::line(&mut rotated, v2(2, 8), v2(13, 8), b'o', 1);
+    for x in 3..13 {
+        assert!((0..16).any(|y| canvas.get_pixel(v2(x, y)) == Some(b'o')));
+    }
+
+    // The pixels at the i16 edge don't overflow
+    let mut buffer = [b'.'; 8];
+    let mut canvas = Canvas::new(&mut buffer, v2(8,1)).unwrap();
+    let mut shifted = Transform::new(&mut canvas, Affine::translate(v2(-32760, 0)));
+    unsafe {
+        shifted._hline(v2(32761, 0), 7, b'b');
+        shifted._vline(v2(32761, 0), u16::MAX, b'v');
+        shifted._rect(v2(32763, 0), v2(i16::MAX, 0), b'c');
+        shifted._pixel(v2(i16::MAX, 0), b'a');
+    }
+    assert_eq!(core::str::from_utf8(&buffer).unwrap(), ".vbcccca");
+}